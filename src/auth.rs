@@ -1,18 +1,20 @@
 use crate::onvif;
 
-pub fn get_auth_header(user: &str, password: &str) -> String {
+/// Generate the WS-UsernameToken header. `clock_skew` is added to the local
+/// time so that the creation date matches the camera clock.
+pub fn get_auth_header(user: &str, password: &str, clock_skew: chrono::Duration) -> String {
     use rand::prelude::*;
 
     let mut nonce = [0u8; 22];
     rand::thread_rng().fill_bytes(&mut nonce);
-    let nonce_b64 = base64::encode(&nonce);
+    let nonce_b64 = base64::encode(nonce);
 
-    let created = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    let created = (chrono::Utc::now() + clock_skew)
+        .format("%Y-%m-%dT%H:%M:%S")
+        .to_string();
     let digest_b64 = get_digest_b64(&nonce_b64, &created, password);
 
-    let auth = onvif::auth_header(&user, &digest_b64, &nonce_b64, &created);
-
-    auth
+    onvif::auth_header(user, &digest_b64, &nonce_b64, &created)
 }
 
 fn get_digest_b64(nonce_b64: &str, date: &str, password: &str) -> String {
@@ -23,11 +25,10 @@ fn get_digest_b64(nonce_b64: &str, date: &str, password: &str) -> String {
     let digest_base = &digest_base_buf[..];
 
     let mut digest_buf = [0u8; 20];
-    let digest_size = sha1(&digest_base, &mut digest_buf);
+    let digest_size = sha1(digest_base, &mut digest_buf);
     let digest = &digest_buf[..digest_size];
 
-    let digest_b64 = base64::encode(&digest);
-    digest_b64
+    base64::encode(digest)
 }
 
 fn sha1(buf: &[u8], out: &mut [u8]) -> usize {
@@ -39,6 +40,125 @@ fn sha1(buf: &[u8], out: &mut [u8]) -> usize {
     hasher.output_bytes()
 }
 
+/// Generate the value of the HTTP `Authorization` header answering the
+/// `WWW-Authenticate` challenges of a 401 response. Digest is preferred over
+/// Basic, which is used if no Digest challenge is supported, e.g. with another
+/// algorithm than MD5. None is returned if no challenge is supported.
+pub fn http_auth_header(
+    challenges: &[&str],
+    method: &str,
+    uri: &str,
+    user: &str,
+    password: &str,
+) -> Option<String> {
+    use rand::prelude::*;
+
+    let scheme = |c: &&str, name: &str| {
+        c.trim_start()
+            .get(..name.len())
+            .is_some_and(|s| s.eq_ignore_ascii_case(name))
+    };
+
+    let cnonce = format!("{:016x}", rand::thread_rng().gen::<u64>());
+    challenges
+        .iter()
+        .filter(|c| scheme(c, "Digest"))
+        .find_map(|c| digest_auth(&c.trim_start()[6..], method, uri, user, password, &cnonce))
+        .or_else(|| {
+            challenges
+                .iter()
+                .any(|c| scheme(c, "Basic"))
+                .then(|| format!("Basic {}", base64::encode(format!("{}:{}", user, password))))
+        })
+}
+
+/// Answer an HTTP Digest challenge (RFC 2617), only the MD5 algorithm is supported
+fn digest_auth(
+    challenge: &str,
+    method: &str,
+    uri: &str,
+    user: &str,
+    password: &str,
+    cnonce: &str,
+) -> Option<String> {
+    let params = parse_challenge(challenge);
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    };
+
+    if let Some(algorithm) = param("algorithm") {
+        if !algorithm.eq_ignore_ascii_case("MD5") {
+            warn!("unsupported digest algorithm {}", algorithm);
+            return None;
+        }
+    }
+
+    let realm = param("realm")?;
+    let nonce = param("nonce")?;
+    let qop_auth = param("qop").is_some_and(|q| q.split(',').any(|q| q.trim() == "auth"));
+
+    let ha1 = md5_hex(&format!("{}:{}:{}", user, realm, password));
+    let ha2 = md5_hex(&format!("{}:{}", method, uri));
+
+    let mut header = format!(
+        r#"Digest username="{}", realm="{}", nonce="{}", uri="{}""#,
+        user, realm, nonce, uri
+    );
+    if qop_auth {
+        let nc = "00000001";
+        let response = md5_hex(&format!("{}:{}:{}:{}:auth:{}", ha1, nonce, nc, cnonce, ha2));
+        header += &format!(
+            r#", qop=auth, nc={}, cnonce="{}", response="{}""#,
+            nc, cnonce, response
+        );
+    } else {
+        let response = md5_hex(&format!("{}:{}:{}", ha1, nonce, ha2));
+        header += &format!(r#", response="{}""#, response);
+    }
+    if let Some(opaque) = param("opaque") {
+        header += &format!(r#", opaque="{}""#, opaque);
+    }
+
+    Some(header)
+}
+
+/// Split the `key=value` parameters of a challenge, values may be quoted
+fn parse_challenge(challenge: &str) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut rest = challenge.trim();
+
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim().trim_start_matches(',').trim().to_string();
+        rest = rest[eq + 1..].trim_start();
+
+        let value = if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            let value = &quoted[..end];
+            rest = quoted.get(end + 1..).unwrap_or("");
+            value
+        } else {
+            let end = rest.find(',').unwrap_or(rest.len());
+            let value = rest[..end].trim();
+            rest = &rest[end..];
+            value
+        };
+        params.push((key, value.to_string()));
+    }
+
+    params
+}
+
+fn md5_hex(s: &str) -> String {
+    use crypto::digest::Digest;
+    use crypto::md5::Md5;
+    let mut hasher = Md5::new();
+    hasher.input_str(s);
+    hasher.result_str()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,4 +172,32 @@ mod tests {
 
         assert_eq!(get_digest_b64(nonce, date, password), expected);
     }
+
+    #[test]
+    fn test_digest_rfc2617_example() {
+        let challenge = r#"realm="testrealm@host.com", qop="auth,auth-int", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", opaque="5ccc069c403ebaf9f0171e9517f40e41""#;
+        let header = digest_auth(
+            challenge,
+            "GET",
+            "/dir/index.html",
+            "Mufasa",
+            "Circle Of Life",
+            "0a4f113b",
+        )
+        .unwrap();
+
+        assert!(header.contains(r#"response="6629fae49393a05397450978507c4ef1""#));
+        assert!(header.contains(r#"opaque="5ccc069c403ebaf9f0171e9517f40e41""#));
+    }
+
+    #[test]
+    fn test_http_auth_basic_fallback() {
+        let challenges = [
+            r#"Digest realm="cam", nonce="abc", algorithm=SHA-256"#,
+            r#"Basic realm="cam""#,
+        ];
+        let header = http_auth_header(&challenges, "GET", "/snap.jpg", "user", "pass");
+
+        assert_eq!(header.as_deref(), Some("Basic dXNlcjpwYXNz"));
+    }
 }
@@ -0,0 +1,152 @@
+//! Device management responses
+
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use roxmltree::Node;
+
+use crate::error::{Error, Result};
+use crate::xml;
+
+/// Device identification returned by GetDeviceInformation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInformation {
+    pub manufacturer: String,
+    pub model: String,
+    pub firmware_version: String,
+    pub serial_number: String,
+    pub hardware_id: String,
+}
+
+/// Source of the device time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateTimeType {
+    /// Time set with SetSystemDateAndTime
+    Manual,
+    /// Time synchronized with NTP
+    Ntp,
+}
+
+impl fmt::Display for DateTimeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DateTimeType::Manual => "Manual",
+            DateTimeType::Ntp => "NTP",
+        })
+    }
+}
+
+/// Device date, time and time zone settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemDateAndTime {
+    pub date_time_type: DateTimeType,
+    /// True if daylight savings are on
+    pub daylight_savings: bool,
+    /// POSIX time zone string, such as "CET-1CEST,M3.5.0,M10.5.0/3"
+    pub time_zone: Option<String>,
+    /// UTC date and time, only sent with `DateTimeType::Manual`
+    pub utc_date_time: Option<DateTime<Utc>>,
+    /// Local time of the device, ignored by `set_system_date_and_time()`
+    pub local_date_time: Option<NaiveDateTime>,
+}
+
+impl SystemDateAndTime {
+    /// Return an error if the settings cannot be sent, a manual setting
+    /// requires `utc_date_time`
+    pub(crate) fn check(&self) -> Result<()> {
+        if self.date_time_type == DateTimeType::Manual && self.utc_date_time.is_none() {
+            return Err(Error::InvalidArgument(
+                "manual date and time without UTC date and time".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Parse a `tt:DateTime` element made of `Date` and `Time` children
+fn parse_naive_datetime(node: Node) -> Result<NaiveDateTime> {
+    let field = |parent: &str, name: &str| -> Result<u32> {
+        xml::child(node, parent)
+            .and_then(|n| xml::child_text(n, name))
+            .and_then(|t| t.parse().ok())
+            .ok_or_else(|| Error::InvalidResponse(format!("invalid {}/{}", parent, name)))
+    };
+
+    let (year, month, day) = (
+        field("Date", "Year")?,
+        field("Date", "Month")?,
+        field("Date", "Day")?,
+    );
+    let (hour, minute, second) = (
+        field("Time", "Hour")?,
+        field("Time", "Minute")?,
+        field("Time", "Second")?,
+    );
+
+    NaiveDate::from_ymd_opt(year as i32, month, day)
+        .and_then(|d| d.and_hms_opt(hour, minute, second))
+        .ok_or_else(|| Error::InvalidResponse("invalid date".into()))
+}
+
+/// Parse a `tt:DateTime` element in UTC
+fn parse_datetime(node: Node) -> Result<DateTime<Utc>> {
+    Ok(Utc.from_utc_datetime(&parse_naive_datetime(node)?))
+}
+
+/// Parse the camera UTC time from a GetSystemDateAndTime response
+pub fn parse_utc_datetime(xml: &str) -> Result<DateTime<Utc>> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let node = xml::descendant(doc.root(), "UTCDateTime")
+        .ok_or_else(|| Error::InvalidResponse("missing UTCDateTime".into()))?;
+    parse_datetime(node)
+}
+
+/// Parse the settings from a GetSystemDateAndTime response
+pub fn parse_system_date_and_time(xml: &str) -> Result<SystemDateAndTime> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let node = xml::descendant(doc.root(), "SystemDateAndTime")
+        .ok_or_else(|| Error::InvalidResponse("missing SystemDateAndTime".into()))?;
+
+    let date_time_type = match xml::child_text(node, "DateTimeType") {
+        Some("Manual") => DateTimeType::Manual,
+        Some("NTP") => DateTimeType::Ntp,
+        other => {
+            return Err(Error::InvalidResponse(format!(
+                "invalid DateTimeType {:?}",
+                other
+            )))
+        }
+    };
+
+    Ok(SystemDateAndTime {
+        date_time_type,
+        daylight_savings: xml::child_text(node, "DaylightSavings")
+            .and_then(xml::parse_bool)
+            .unwrap_or(false),
+        time_zone: xml::child(node, "TimeZone").and_then(|n| xml::child_parse(n, "TZ")),
+        utc_date_time: xml::child(node, "UTCDateTime")
+            .map(parse_datetime)
+            .transpose()?,
+        local_date_time: xml::child(node, "LocalDateTime")
+            .map(parse_naive_datetime)
+            .transpose()?,
+    })
+}
+
+/// Parse the device identification from a GetDeviceInformation response
+pub fn parse_device_information(xml: &str) -> Result<DeviceInformation> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let node = xml::descendant(doc.root(), "GetDeviceInformationResponse")
+        .ok_or_else(|| Error::InvalidResponse("missing GetDeviceInformationResponse".into()))?;
+
+    // Some cameras send empty elements, which are kept as empty strings
+    let text = |name| xml::child_parse(node, name).unwrap_or_default();
+
+    Ok(DeviceInformation {
+        manufacturer: text("Manufacturer"),
+        model: text("Model"),
+        firmware_version: text("FirmwareVersion"),
+        serial_number: text("SerialNumber"),
+        hardware_id: text("HardwareId"),
+    })
+}
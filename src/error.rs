@@ -1,6 +1,9 @@
 use thiserror::Error;
 use ureq::Response;
 
+use crate::fault::{self, FaultSubcode};
+use crate::ptz::FloatRange;
+
 /// Crate result type
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -8,11 +11,55 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("{0:?}")]
-    Response(Response),
+    Response(Box<Response>),
+    #[error("SOAP fault {code} {subcodes:?}: {reason}")]
+    SoapFault {
+        /// Fault code, usually `env:Sender` or `env:Receiver`
+        code: String,
+        /// Subcode chain, from the most generic to the most specific
+        subcodes: Vec<FaultSubcode>,
+        /// Human readable reason
+        reason: String,
+        /// Text content of the fault detail, if any
+        detail: Option<String>,
+    },
     #[error("missing profile token")]
     MissingProfile,
     #[error("invalid URL")]
     InvalidUrl(#[from] url::ParseError),
     #[error("xml parse error")]
     XmlParseError(#[from] roxmltree::Error),
+    #[error("invalid response: {0}")]
+    InvalidResponse(String),
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("PTZ value {value} out of range [{}, {}]", range.min, range.max)]
+    OutOfRange { value: f32, range: FloatRange },
+    #[error("PTZ move not completed after {0:?}")]
+    MoveTimeout(std::time::Duration),
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    #[cfg(feature = "async")]
+    #[error("HTTP error")]
+    Http(#[from] reqwest::Error),
+}
+
+impl Error {
+    /// Build the error of an HTTP error response, decoding the SOAP fault if any
+    pub(crate) fn from_status(status: u16, status_text: &str, body: &str) -> Self {
+        trace!("error response body = {}", body);
+
+        match fault::parse_fault(body) {
+            Some(fault) => fault,
+            None => Error::Response(Box::new(Response::new(status, status_text, body))),
+        }
+    }
+
+    /// Return true if the error is a SOAP fault containing the given subcode
+    pub fn is_fault(&self, subcode: &FaultSubcode) -> bool {
+        match self {
+            Error::SoapFault { subcodes, .. } => subcodes.contains(subcode),
+            _ => false,
+        }
+    }
 }
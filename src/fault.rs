@@ -0,0 +1,101 @@
+//! SOAP 1.2 fault parsing
+
+use roxmltree::Node;
+
+use crate::error::Error;
+use crate::namespaces::SOAP_ENV;
+
+/// Well-known ONVIF fault subcodes (`ter:` namespace)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FaultSubcode {
+    /// The requested action is optional and not implemented by the device
+    ActionNotSupported,
+    /// The arguments are invalid
+    InvalidArgs,
+    /// An argument value is invalid
+    InvalidArgVal,
+    /// The requested entity (node, preset, ...) does not exist
+    NoEntity,
+    /// The requested profile token does not exist
+    NoProfile,
+    /// The sender is not authorized, usually because of bad credentials
+    NotAuthorized,
+    /// The operation is not permitted by the device
+    OperationProhibited,
+    /// Any other subcode, stored as received
+    Other(String),
+}
+
+impl FaultSubcode {
+    /// Map a subcode QName such as `ter:NotAuthorized`. The namespace prefix is
+    /// ignored since cameras are free to choose it.
+    fn from_qname(qname: &str) -> Self {
+        let local = qname.rsplit(':').next().unwrap_or(qname);
+        match local {
+            "ActionNotSupported" => FaultSubcode::ActionNotSupported,
+            "InvalidArgs" => FaultSubcode::InvalidArgs,
+            "InvalidArgVal" => FaultSubcode::InvalidArgVal,
+            "NoEntity" => FaultSubcode::NoEntity,
+            "NoProfile" => FaultSubcode::NoProfile,
+            "NotAuthorized" => FaultSubcode::NotAuthorized,
+            "OperationProhibited" => FaultSubcode::OperationProhibited,
+            _ => FaultSubcode::Other(qname.to_string()),
+        }
+    }
+}
+
+/// Return the first child element in the SOAP envelope namespace with the given name
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name((SOAP_ENV, name)))
+}
+
+/// Return the trimmed text of the `Value` child
+fn value(node: Node) -> Option<String> {
+    child(node, "Value")
+        .and_then(|v| v.text())
+        .map(|t| t.trim().to_string())
+}
+
+/// Parse a SOAP response body, returning `Error::SoapFault` if it holds a fault
+pub fn parse_fault(xml: &str) -> Option<Error> {
+    let doc = roxmltree::Document::parse(xml).ok()?;
+    let fault = doc
+        .descendants()
+        .find(|n| n.has_tag_name((SOAP_ENV, "Fault")))?;
+
+    let code_node = child(fault, "Code");
+    let code = code_node.and_then(value).unwrap_or_default();
+
+    // Subcodes are nested in each other, from the most generic to the most specific
+    let mut subcodes = Vec::new();
+    let mut current = code_node.and_then(|n| child(n, "Subcode"));
+    while let Some(subcode) = current {
+        if let Some(v) = value(subcode) {
+            subcodes.push(FaultSubcode::from_qname(&v));
+        }
+        current = child(subcode, "Subcode");
+    }
+
+    let reason = child(fault, "Reason")
+        .and_then(|r| child(r, "Text"))
+        .and_then(|t| t.text())
+        .map(|t| t.trim().to_string())
+        .unwrap_or_default();
+
+    let detail = child(fault, "Detail").map(|d| {
+        d.descendants()
+            .filter(|n| n.is_text())
+            .filter_map(|n| n.text())
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+
+    Some(Error::SoapFault {
+        code,
+        subcodes,
+        reason,
+        detail,
+    })
+}
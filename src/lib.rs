@@ -1,11 +1,47 @@
 #[macro_use]
 extern crate log;
 
+#[cfg(feature = "async")]
+mod async_camera;
 mod auth;
 mod camera;
+mod device;
 mod error;
+mod fault;
+mod media;
 mod namespaces;
+mod network;
 mod onvif;
+mod patrol;
+mod ptz;
+mod services;
+mod session;
+mod tour;
+mod xml;
 
+#[cfg(feature = "async")]
+pub use async_camera::AsyncOnvifCamera;
 pub use camera::OnvifCamera;
+pub use device::{DateTimeType, DeviceInformation, SystemDateAndTime};
 pub use error::{Error, Result};
+pub use fault::FaultSubcode;
+pub use media::{
+    AudioEncoderConfiguration, AudioSourceConfiguration, MediaUri, Profile, Rectangle, Resolution,
+    StreamSetup, StreamType, TransportProtocol, VideoEncoderConfiguration, VideoEncoding,
+    VideoSourceConfiguration,
+};
+pub use network::{
+    DnsInformation, HostnameInformation, Ipv4Configuration, Ipv4Setup, Ipv6Configuration, Ipv6Dhcp,
+    Ipv6Setup, NetworkGateway, NetworkHost, NetworkInterface, NetworkInterfaceSetup,
+    NtpInformation, PrefixedAddress, PrefixedIpv4Address, PrefixedIpv6Address,
+};
+pub use patrol::{Patrol, PatrolStep, PatrolStop};
+pub use ptz::{
+    FloatRange, MoveStatus, Preset, PtzConfiguration, PtzConfigurationOptions, PtzNode, PtzSpaces,
+    PtzStatus, PtzVector, RangePolicy, Space, TranslationSpace, WaitSetup,
+};
+pub use services::Service;
+pub use tour::{
+    PresetTour, PresetTourOptions, TourDirection, TourOperation, TourSpot, TourStartingCondition,
+    TourState,
+};
@@ -0,0 +1,332 @@
+//! Media types and responses
+
+use std::fmt;
+use std::time::Duration;
+
+use roxmltree::Node;
+
+use crate::error::{Error, Result};
+use crate::ptz::{self, PtzConfiguration};
+use crate::xml;
+
+/// Media profile, grouping the configurations used for a stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub token: String,
+    pub name: String,
+    /// True if the profile cannot be deleted
+    pub fixed: bool,
+    pub video_source: Option<VideoSourceConfiguration>,
+    pub video_encoder: Option<VideoEncoderConfiguration>,
+    pub audio_source: Option<AudioSourceConfiguration>,
+    pub audio_encoder: Option<AudioEncoderConfiguration>,
+    pub ptz: Option<PtzConfiguration>,
+}
+
+/// Rectangle in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rectangle {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Video source configuration of a profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoSourceConfiguration {
+    pub token: String,
+    pub name: String,
+    /// Token of the physical video input
+    pub source_token: String,
+    /// Part of the video source used for the stream
+    pub bounds: Option<Rectangle>,
+}
+
+/// Video codec
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VideoEncoding {
+    Jpeg,
+    Mpeg4,
+    H264,
+    /// Any other encoding, stored as received
+    Other(String),
+}
+
+impl VideoEncoding {
+    fn parse(s: &str) -> Self {
+        match s {
+            "JPEG" => VideoEncoding::Jpeg,
+            "MPEG4" => VideoEncoding::Mpeg4,
+            "H264" => VideoEncoding::H264,
+            other => VideoEncoding::Other(other.to_string()),
+        }
+    }
+}
+
+/// Video resolution in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Video encoder configuration of a profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoEncoderConfiguration {
+    pub token: String,
+    pub name: String,
+    pub encoding: VideoEncoding,
+    pub resolution: Resolution,
+    /// Relative quality, the range depends on the device
+    pub quality: f32,
+    /// Maximum frame rate in frames per second
+    pub frame_rate_limit: Option<u32>,
+    /// Interval at which images are encoded, 1 meaning every frame
+    pub encoding_interval: Option<u32>,
+    /// Maximum bitrate in kbps
+    pub bitrate_limit: Option<u32>,
+    /// Group of video frames length, for H264 and MPEG4
+    pub gov_length: Option<u32>,
+    /// H264 or MPEG4 profile, such as "Main" or "High"
+    pub encoding_profile: Option<String>,
+}
+
+/// Audio source configuration of a profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioSourceConfiguration {
+    pub token: String,
+    pub name: String,
+    /// Token of the physical audio input
+    pub source_token: String,
+}
+
+/// Audio encoder configuration of a profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioEncoderConfiguration {
+    pub token: String,
+    pub name: String,
+    /// Audio codec, such as "G711", "G726" or "AAC"
+    pub encoding: String,
+    /// Bitrate in kbps
+    pub bitrate: Option<u32>,
+    /// Sample rate in kHz
+    pub sample_rate: Option<u32>,
+}
+
+/// Stream type requested in GetStreamUri
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamType {
+    RtpUnicast,
+    RtpMulticast,
+}
+
+impl fmt::Display for StreamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StreamType::RtpUnicast => "RTP-Unicast",
+            StreamType::RtpMulticast => "RTP-Multicast",
+        })
+    }
+}
+
+/// Transport protocol requested in GetStreamUri
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportProtocol {
+    Udp,
+    /// RTP over TCP, deprecated by ONVIF but still used by some devices
+    Tcp,
+    /// RTP over RTSP over TCP
+    Rtsp,
+    /// RTP over RTSP over HTTP
+    Http,
+}
+
+impl fmt::Display for TransportProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TransportProtocol::Udp => "UDP",
+            TransportProtocol::Tcp => "TCP",
+            TransportProtocol::Rtsp => "RTSP",
+            TransportProtocol::Http => "HTTP",
+        })
+    }
+}
+
+/// Parameters of a GetStreamUri request. The default is RTP unicast over UDP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamSetup {
+    pub stream: StreamType,
+    pub transport: TransportProtocol,
+    /// Insert the camera user name and password in the returned URI
+    pub include_credentials: bool,
+}
+
+impl Default for StreamSetup {
+    fn default() -> Self {
+        Self {
+            stream: StreamType::RtpUnicast,
+            transport: TransportProtocol::Udp,
+            include_credentials: false,
+        }
+    }
+}
+
+/// URI returned by GetStreamUri or GetSnapshotUri
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaUri {
+    pub uri: String,
+    /// True if the URI is only valid until a connection is made
+    pub invalid_after_connect: bool,
+    /// True if the URI is only valid until the device reboots
+    pub invalid_after_reboot: bool,
+    /// Duration of validity of the URI, zero meaning unlimited
+    pub timeout: Option<Duration>,
+}
+
+impl MediaUri {
+    /// Insert the user name and password in the URI
+    pub(crate) fn set_credentials(&mut self, user: &str, password: Option<&str>) -> Result<()> {
+        let mut u = url::Url::parse(&self.uri)?;
+        u.set_username(user)
+            .and_then(|_| u.set_password(password))
+            .map_err(|_| {
+                Error::InvalidResponse(format!("cannot set credentials in {}", &self.uri))
+            })?;
+        self.uri = u.to_string();
+        Ok(())
+    }
+}
+
+fn token(node: Node) -> String {
+    node.attribute("token").unwrap_or_default().to_string()
+}
+
+fn name(node: Node) -> String {
+    xml::child_parse(node, "Name").unwrap_or_default()
+}
+
+fn parse_video_source(node: Node) -> Result<VideoSourceConfiguration> {
+    let bounds = xml::child(node, "Bounds").and_then(|b| {
+        Some(Rectangle {
+            x: b.attribute("x")?.parse().ok()?,
+            y: b.attribute("y")?.parse().ok()?,
+            width: b.attribute("width")?.parse().ok()?,
+            height: b.attribute("height")?.parse().ok()?,
+        })
+    });
+
+    Ok(VideoSourceConfiguration {
+        token: token(node),
+        name: name(node),
+        source_token: xml::required(node, "SourceToken")?,
+        bounds,
+    })
+}
+
+fn parse_video_encoder(node: Node) -> Result<VideoEncoderConfiguration> {
+    let resolution = xml::required_child(node, "Resolution")?;
+    let rate_control = xml::child(node, "RateControl");
+    let rate = |name| rate_control.and_then(|n| xml::child_parse(n, name));
+    let codec = xml::child(node, "H264").or_else(|| xml::child(node, "MPEG4"));
+
+    Ok(VideoEncoderConfiguration {
+        token: token(node),
+        name: name(node),
+        encoding: VideoEncoding::parse(xml::required::<String>(node, "Encoding")?.as_str()),
+        resolution: Resolution {
+            width: xml::required(resolution, "Width")?,
+            height: xml::required(resolution, "Height")?,
+        },
+        quality: xml::child_parse(node, "Quality").unwrap_or_default(),
+        frame_rate_limit: rate("FrameRateLimit"),
+        encoding_interval: rate("EncodingInterval"),
+        bitrate_limit: rate("BitrateLimit"),
+        gov_length: codec.and_then(|n| xml::child_parse(n, "GovLength")),
+        encoding_profile: codec.and_then(|n| {
+            xml::child_parse(n, "H264Profile").or_else(|| xml::child_parse(n, "Mpeg4Profile"))
+        }),
+    })
+}
+
+fn parse_audio_source(node: Node) -> Result<AudioSourceConfiguration> {
+    Ok(AudioSourceConfiguration {
+        token: token(node),
+        name: name(node),
+        source_token: xml::required(node, "SourceToken")?,
+    })
+}
+
+fn parse_audio_encoder(node: Node) -> Result<AudioEncoderConfiguration> {
+    Ok(AudioEncoderConfiguration {
+        token: token(node),
+        name: name(node),
+        encoding: xml::required(node, "Encoding")?,
+        bitrate: xml::child_parse(node, "Bitrate"),
+        sample_rate: xml::child_parse(node, "SampleRate"),
+    })
+}
+
+/// Parse an optional configuration of a profile, ignoring it if it is invalid
+/// so that one unusual configuration does not hide the whole profile
+fn optional_config<T>(node: Option<Node>, parse: fn(Node) -> Result<T>) -> Option<T> {
+    let node = node?;
+    parse(node)
+        .map_err(|e| warn!("ignoring invalid {}: {}", node.tag_name().name(), e))
+        .ok()
+}
+
+fn parse_profile(node: Node) -> Profile {
+    let config = |name| xml::child(node, name);
+
+    Profile {
+        token: token(node),
+        name: name(node),
+        fixed: node
+            .attribute("fixed")
+            .and_then(xml::parse_bool)
+            .unwrap_or(false),
+        video_source: optional_config(config("VideoSourceConfiguration"), parse_video_source),
+        video_encoder: optional_config(config("VideoEncoderConfiguration"), parse_video_encoder),
+        audio_source: optional_config(config("AudioSourceConfiguration"), parse_audio_source),
+        audio_encoder: optional_config(config("AudioEncoderConfiguration"), parse_audio_encoder),
+        ptz: optional_config(config("PTZConfiguration"), ptz::parse_configuration),
+    }
+}
+
+/// Parse the profiles from a GetProfiles response
+pub fn parse_profiles(xml: &str) -> Result<Vec<Profile>> {
+    Ok(roxmltree::Document::parse(xml)?
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "Profiles")
+        .map(parse_profile)
+        .collect())
+}
+
+/// Parse only the tokens of the profiles from a GetProfiles response
+pub fn parse_profile_tokens(xml: &str) -> Result<Vec<String>> {
+    Ok(roxmltree::Document::parse(xml)?
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "Profiles")
+        .map(token)
+        .collect())
+}
+
+/// Parse the URI from a GetStreamUri or GetSnapshotUri response
+pub fn parse_media_uri(xml: &str) -> Result<MediaUri> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let node = xml::descendant(doc.root(), "MediaUri")
+        .ok_or_else(|| Error::InvalidResponse("missing MediaUri".into()))?;
+    let flag = |name| {
+        xml::child_text(node, name)
+            .and_then(xml::parse_bool)
+            .unwrap_or(false)
+    };
+
+    Ok(MediaUri {
+        uri: xml::required(node, "Uri")?,
+        invalid_after_connect: flag("InvalidAfterConnect"),
+        invalid_after_reboot: flag("InvalidAfterReboot"),
+        timeout: xml::child_text(node, "Timeout").and_then(xml::parse_duration),
+    })
+}
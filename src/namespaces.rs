@@ -35,3 +35,4 @@ pub const OVF_PTS_TGS: &str =
     "http://www.onvif.org/ver10/tptz/PanTiltSpaces/TranslationGenericSpace";
 pub const OVF_PTS_PGS: &str = "http://www.onvif.org/ver10/tptz/PanTiltSpaces/PositionGenericSpace";
 pub const OVF_PTS_GSS: &str = "http://www.onvif.org/ver10/tptz/PanTiltSpaces/GenericSpeedSpace";
+pub const OVF_PTS_TSFOV: &str = "http://www.onvif.org/ver10/tptz/PanTiltSpaces/TranslationSpaceFov";
@@ -0,0 +1,365 @@
+//! Network configuration types and responses
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use roxmltree::Node;
+
+use crate::error::{Error, Result};
+use crate::xml;
+
+/// Address of a server, such as an NTP server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkHost {
+    Ipv4(Ipv4Addr),
+    Ipv6(Ipv6Addr),
+    /// Host name resolved by the device
+    Dns(String),
+}
+
+impl fmt::Display for NetworkHost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkHost::Ipv4(addr) => addr.fmt(f),
+            NetworkHost::Ipv6(addr) => addr.fmt(f),
+            NetworkHost::Dns(name) => f.write_str(name),
+        }
+    }
+}
+
+/// Hostname settings of the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostnameInformation {
+    /// True if the hostname is obtained with DHCP
+    pub from_dhcp: bool,
+    pub name: Option<String>,
+}
+
+/// NTP settings of the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NtpInformation {
+    /// True if the NTP servers are obtained with DHCP
+    pub from_dhcp: bool,
+    pub ntp_from_dhcp: Vec<NetworkHost>,
+    pub ntp_manual: Vec<NetworkHost>,
+}
+
+/// DNS settings of the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsInformation {
+    /// True if the DNS servers are obtained with DHCP
+    pub from_dhcp: bool,
+    pub search_domains: Vec<String>,
+    pub dns_from_dhcp: Vec<IpAddr>,
+    pub dns_manual: Vec<IpAddr>,
+}
+
+/// IP address with the length of its network prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrefixedAddress<A> {
+    pub address: A,
+    pub prefix_length: u8,
+}
+
+/// IPv4 address with the length of its network prefix
+pub type PrefixedIpv4Address = PrefixedAddress<Ipv4Addr>;
+
+/// IPv6 address with the length of its network prefix
+pub type PrefixedIpv6Address = PrefixedAddress<Ipv6Addr>;
+
+/// DHCP mode of an IPv6 configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ipv6Dhcp {
+    Auto,
+    Stateful,
+    Stateless,
+    Off,
+}
+
+impl Ipv6Dhcp {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "Auto" => Some(Ipv6Dhcp::Auto),
+            "Stateful" => Some(Ipv6Dhcp::Stateful),
+            "Stateless" => Some(Ipv6Dhcp::Stateless),
+            "Off" => Some(Ipv6Dhcp::Off),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Ipv6Dhcp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Ipv6Dhcp::Auto => "Auto",
+            Ipv6Dhcp::Stateful => "Stateful",
+            Ipv6Dhcp::Stateless => "Stateless",
+            Ipv6Dhcp::Off => "Off",
+        })
+    }
+}
+
+/// IPv4 configuration of a network interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ipv4Configuration {
+    pub enabled: bool,
+    /// True if the address is obtained with DHCP
+    pub dhcp: bool,
+    pub manual: Vec<PrefixedIpv4Address>,
+    pub link_local: Option<PrefixedIpv4Address>,
+    pub from_dhcp: Option<PrefixedIpv4Address>,
+}
+
+/// IPv6 configuration of a network interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ipv6Configuration {
+    pub enabled: bool,
+    pub dhcp: Option<Ipv6Dhcp>,
+    /// True if router advertisements are used
+    pub accept_router_advert: Option<bool>,
+    pub manual: Vec<PrefixedIpv6Address>,
+    pub link_local: Vec<PrefixedIpv6Address>,
+    pub from_dhcp: Vec<PrefixedIpv6Address>,
+    /// Addresses obtained with router advertisements
+    pub from_ra: Vec<PrefixedIpv6Address>,
+}
+
+/// Network interface of the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkInterface {
+    pub token: String,
+    pub enabled: bool,
+    /// Interface name, such as "eth0"
+    pub name: Option<String>,
+    /// MAC address
+    pub hw_address: Option<String>,
+    pub mtu: Option<u32>,
+    pub ipv4: Option<Ipv4Configuration>,
+    pub ipv6: Option<Ipv6Configuration>,
+}
+
+/// IPv4 settings of `NetworkInterfaceSetup`, None values are left unchanged
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Ipv4Setup {
+    pub enabled: Option<bool>,
+    pub dhcp: Option<bool>,
+    /// Static addresses
+    pub manual: Vec<PrefixedIpv4Address>,
+}
+
+/// IPv6 settings of `NetworkInterfaceSetup`, None values are left unchanged
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Ipv6Setup {
+    pub enabled: Option<bool>,
+    pub accept_router_advert: Option<bool>,
+    pub dhcp: Option<Ipv6Dhcp>,
+    /// Static addresses
+    pub manual: Vec<PrefixedIpv6Address>,
+}
+
+/// Settings of a SetNetworkInterfaces request, None values are left unchanged
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NetworkInterfaceSetup {
+    pub enabled: Option<bool>,
+    pub mtu: Option<u32>,
+    pub ipv4: Option<Ipv4Setup>,
+    pub ipv6: Option<Ipv6Setup>,
+}
+
+/// Default gateways of the device
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NetworkGateway {
+    pub ipv4_address: Vec<Ipv4Addr>,
+    pub ipv6_address: Vec<Ipv6Addr>,
+}
+
+/// Parse a `tt:NetworkHost` element, None if invalid
+fn parse_network_host(node: Node) -> Option<NetworkHost> {
+    match xml::child_text(node, "Type")? {
+        "IPv4" => xml::child_parse(node, "IPv4Address").map(NetworkHost::Ipv4),
+        "IPv6" => xml::child_parse(node, "IPv6Address").map(NetworkHost::Ipv6),
+        "DNS" => xml::child_parse(node, "DNSname").map(NetworkHost::Dns),
+        _ => None,
+    }
+}
+
+/// Parse a `tt:IPAddress` element, None if invalid
+fn parse_ip_address(node: Node) -> Option<IpAddr> {
+    match xml::child_text(node, "Type")? {
+        "IPv4" => xml::child_parse(node, "IPv4Address").map(IpAddr::V4),
+        "IPv6" => xml::child_parse(node, "IPv6Address").map(IpAddr::V6),
+        _ => None,
+    }
+}
+
+fn from_dhcp(node: Node) -> bool {
+    xml::child_text(node, "FromDHCP")
+        .and_then(xml::parse_bool)
+        .unwrap_or(false)
+}
+
+/// Parse a `tt:PrefixedIPv4Address` or `tt:PrefixedIPv6Address` element, None
+/// if invalid
+fn parse_prefixed_address<A: FromStr>(node: Node) -> Option<PrefixedAddress<A>> {
+    Some(PrefixedAddress {
+        address: xml::child_parse(node, "Address")?,
+        prefix_length: xml::child_parse(node, "PrefixLength")?,
+    })
+}
+
+/// Parse the valid prefixed addresses of the `config` children named `name`
+fn prefixed_addresses<A: FromStr>(config: Option<Node>, name: &str) -> Vec<PrefixedAddress<A>> {
+    config
+        .map(|n| {
+            xml::children(n, name)
+                .filter_map(parse_prefixed_address)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn enabled(node: Node) -> bool {
+    xml::child_text(node, "Enabled")
+        .and_then(xml::parse_bool)
+        .unwrap_or(false)
+}
+
+fn parse_ipv4(node: Node) -> Ipv4Configuration {
+    let config = xml::child(node, "Config");
+    let address = |name| {
+        config
+            .and_then(|n| xml::child(n, name))
+            .and_then(parse_prefixed_address)
+    };
+
+    Ipv4Configuration {
+        enabled: enabled(node),
+        dhcp: config
+            .and_then(|n| xml::child_text(n, "DHCP"))
+            .and_then(xml::parse_bool)
+            .unwrap_or(false),
+        manual: prefixed_addresses(config, "Manual"),
+        link_local: address("LinkLocal"),
+        from_dhcp: address("FromDHCP"),
+    }
+}
+
+fn parse_ipv6(node: Node) -> Ipv6Configuration {
+    let config = xml::child(node, "Config");
+
+    Ipv6Configuration {
+        enabled: enabled(node),
+        dhcp: config
+            .and_then(|n| xml::child_text(n, "DHCP"))
+            .and_then(Ipv6Dhcp::parse),
+        accept_router_advert: config
+            .and_then(|n| xml::child_text(n, "AcceptRouterAdvert"))
+            .and_then(xml::parse_bool),
+        manual: prefixed_addresses(config, "Manual"),
+        link_local: prefixed_addresses(config, "LinkLocal"),
+        from_dhcp: prefixed_addresses(config, "FromDHCP"),
+        from_ra: prefixed_addresses(config, "FromRA"),
+    }
+}
+
+fn parse_interface(node: Node) -> NetworkInterface {
+    let info = xml::child(node, "Info");
+
+    NetworkInterface {
+        token: node.attribute("token").unwrap_or_default().to_string(),
+        enabled: enabled(node),
+        name: info.and_then(|n| xml::child_parse(n, "Name")),
+        hw_address: info.and_then(|n| xml::child_parse(n, "HwAddress")),
+        mtu: info.and_then(|n| xml::child_parse(n, "MTU")),
+        ipv4: xml::child(node, "IPv4").map(parse_ipv4),
+        ipv6: xml::child(node, "IPv6").map(parse_ipv6),
+    }
+}
+
+/// Parse the interfaces from a GetNetworkInterfaces response
+pub fn parse_network_interfaces(xml: &str) -> Result<Vec<NetworkInterface>> {
+    Ok(roxmltree::Document::parse(xml)?
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "NetworkInterfaces")
+        .map(parse_interface)
+        .collect())
+}
+
+/// Parse the RebootNeeded flag from a SetNetworkInterfaces or
+/// SetHostnameFromDHCP response
+pub fn parse_reboot_needed(xml: &str) -> Result<bool> {
+    let doc = roxmltree::Document::parse(xml)?;
+    xml::descendant(doc.root(), "RebootNeeded")
+        .and_then(|n| n.text())
+        .and_then(xml::parse_bool)
+        .ok_or_else(|| Error::InvalidResponse("missing or invalid RebootNeeded".into()))
+}
+
+/// Parse the gateways from a GetNetworkDefaultGateway response
+pub fn parse_network_default_gateway(xml: &str) -> Result<NetworkGateway> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let node = xml::descendant(doc.root(), "NetworkGateway")
+        .ok_or_else(|| Error::InvalidResponse("missing NetworkGateway".into()))?;
+
+    Ok(NetworkGateway {
+        ipv4_address: xml::children(node, "IPv4Address")
+            .filter_map(|n| n.text()?.trim().parse().ok())
+            .collect(),
+        ipv6_address: xml::children(node, "IPv6Address")
+            .filter_map(|n| n.text()?.trim().parse().ok())
+            .collect(),
+    })
+}
+
+/// Parse the hostname settings from a GetHostname response
+pub fn parse_hostname(xml: &str) -> Result<HostnameInformation> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let node = xml::descendant(doc.root(), "HostnameInformation")
+        .ok_or_else(|| Error::InvalidResponse("missing HostnameInformation".into()))?;
+
+    Ok(HostnameInformation {
+        from_dhcp: from_dhcp(node),
+        name: xml::child_parse(node, "Name"),
+    })
+}
+
+/// Parse the NTP settings from a GetNTP response
+pub fn parse_ntp(xml: &str) -> Result<NtpInformation> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let node = xml::descendant(doc.root(), "NTPInformation")
+        .ok_or_else(|| Error::InvalidResponse("missing NTPInformation".into()))?;
+    let hosts = |name| {
+        xml::children(node, name)
+            .filter_map(parse_network_host)
+            .collect()
+    };
+
+    Ok(NtpInformation {
+        from_dhcp: from_dhcp(node),
+        ntp_from_dhcp: hosts("NTPFromDHCP"),
+        ntp_manual: hosts("NTPManual"),
+    })
+}
+
+/// Parse the DNS settings from a GetDNS response
+pub fn parse_dns(xml: &str) -> Result<DnsInformation> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let node = xml::descendant(doc.root(), "DNSInformation")
+        .ok_or_else(|| Error::InvalidResponse("missing DNSInformation".into()))?;
+    let addresses = |name| {
+        xml::children(node, name)
+            .filter_map(parse_ip_address)
+            .collect()
+    };
+
+    Ok(DnsInformation {
+        from_dhcp: from_dhcp(node),
+        search_domains: xml::children(node, "SearchDomain")
+            .filter_map(|n| n.text())
+            .map(|t| t.trim().to_string())
+            .collect(),
+        dns_from_dhcp: addresses("DNSFromDHCP"),
+        dns_manual: addresses("DNSManual"),
+    })
+}
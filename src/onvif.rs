@@ -3,8 +3,16 @@
 #![allow(unused)]
 
 use std::fmt::Display;
+use std::net::IpAddr;
+use std::time::Duration;
 
+use chrono::{Datelike, Timelike};
+
+use crate::device::{DateTimeType, SystemDateAndTime};
 use crate::namespaces::*;
+use crate::network::{NetworkGateway, NetworkHost, NetworkInterfaceSetup, PrefixedAddress};
+use crate::ptz::PtzVector;
+use crate::tour::{PresetTour, TourOperation, TourSpot};
 
 pub fn soap_envelop(content: impl Display, auth: Option<impl Display>) -> String {
     if let Some(auth) = auth {
@@ -77,6 +85,57 @@ pub fn get_system_datetime() -> String {
     )
 }
 
+pub fn set_system_datetime(settings: &SystemDateAndTime) -> String {
+    let time_zone = settings
+        .time_zone
+        .as_deref()
+        .map(|tz| {
+            format!(
+                r#"
+    <TimeZone><TZ xmlns="{OVF_SCHEMA}">{tz}</TZ></TimeZone>"#,
+                OVF_SCHEMA = OVF_SCHEMA,
+                tz = escape(tz)
+            )
+        })
+        .unwrap_or_default();
+    // The time is only sent for a manual setting, some cameras reject it with
+    // NTP
+    let utc = settings
+        .utc_date_time
+        .filter(|_| settings.date_time_type == DateTimeType::Manual)
+        .map(|t| {
+            format!(
+                r#"
+    <UTCDateTime>
+        <Date xmlns="{OVF_SCHEMA}"><Year>{year}</Year><Month>{month}</Month><Day>{day}</Day></Date>
+        <Time xmlns="{OVF_SCHEMA}"><Hour>{hour}</Hour><Minute>{minute}</Minute><Second>{second}</Second></Time>
+    </UTCDateTime>"#,
+                OVF_SCHEMA = OVF_SCHEMA,
+                year = t.year(),
+                month = t.month(),
+                day = t.day(),
+                hour = t.hour(),
+                minute = t.minute(),
+                second = t.second(),
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        r#"
+<SetSystemDateAndTime xmlns="{OVF_DEVICE}">
+    <DateTimeType>{date_time_type}</DateTimeType>
+    <DaylightSavings>{daylight_savings}</DaylightSavings>{time_zone}{utc}
+</SetSystemDateAndTime>
+"#,
+        OVF_DEVICE = OVF_DEVICE,
+        date_time_type = settings.date_time_type,
+        daylight_savings = settings.daylight_savings,
+        time_zone = time_zone,
+        utc = utc,
+    )
+}
+
 pub fn get_capabilities() -> String {
     format!(
         r#"
@@ -122,7 +181,261 @@ pub fn get_device_info() -> String {
     )
 }
 
-pub fn get_node(node: impl Display) -> String {
+pub fn get_hostname() -> String {
+    format!(
+        r#"
+<GetHostname xmlns="{OVF_DEVICE}"/>
+"#,
+        OVF_DEVICE = OVF_DEVICE
+    )
+}
+
+pub fn set_hostname(name: &str) -> String {
+    format!(
+        r#"
+<SetHostname xmlns="{OVF_DEVICE}">
+    <Name>{name}</Name>
+</SetHostname>
+"#,
+        OVF_DEVICE = OVF_DEVICE,
+        name = escape(name),
+    )
+}
+
+pub fn set_hostname_from_dhcp(from_dhcp: bool) -> String {
+    format!(
+        r#"
+<SetHostnameFromDHCP xmlns="{OVF_DEVICE}">
+    <FromDHCP>{from_dhcp}</FromDHCP>
+</SetHostnameFromDHCP>
+"#,
+        OVF_DEVICE = OVF_DEVICE,
+        from_dhcp = from_dhcp,
+    )
+}
+
+pub fn get_ntp() -> String {
+    format!(
+        r#"
+<GetNTP xmlns="{OVF_DEVICE}"/>
+"#,
+        OVF_DEVICE = OVF_DEVICE
+    )
+}
+
+/// `tt:NetworkHost` element
+fn network_host(name: &str, host: &NetworkHost) -> String {
+    let (kind, element, value) = match host {
+        NetworkHost::Ipv4(addr) => ("IPv4", "IPv4Address", addr.to_string()),
+        NetworkHost::Ipv6(addr) => ("IPv6", "IPv6Address", addr.to_string()),
+        NetworkHost::Dns(name) => ("DNS", "DNSname", escape(name)),
+    };
+    format!(
+        r#"
+    <{name}>
+        <Type xmlns="{OVF_SCHEMA}">{kind}</Type>
+        <{element} xmlns="{OVF_SCHEMA}">{value}</{element}>
+    </{name}>"#,
+        name = name,
+        kind = kind,
+        element = element,
+        value = value,
+        OVF_SCHEMA = OVF_SCHEMA,
+    )
+}
+
+/// `tt:IPAddress` element
+fn ip_address(name: &str, addr: &IpAddr) -> String {
+    let (kind, element) = match addr {
+        IpAddr::V4(_) => ("IPv4", "IPv4Address"),
+        IpAddr::V6(_) => ("IPv6", "IPv6Address"),
+    };
+    format!(
+        r#"
+    <{name}>
+        <Type xmlns="{OVF_SCHEMA}">{kind}</Type>
+        <{element} xmlns="{OVF_SCHEMA}">{addr}</{element}>
+    </{name}>"#,
+        name = name,
+        kind = kind,
+        element = element,
+        addr = addr,
+        OVF_SCHEMA = OVF_SCHEMA,
+    )
+}
+
+pub fn set_ntp(from_dhcp: bool, manual: &[NetworkHost]) -> String {
+    format!(
+        r#"
+<SetNTP xmlns="{OVF_DEVICE}">
+    <FromDHCP>{from_dhcp}</FromDHCP>{manual}
+</SetNTP>
+"#,
+        OVF_DEVICE = OVF_DEVICE,
+        from_dhcp = from_dhcp,
+        manual = manual
+            .iter()
+            .map(|h| network_host("NTPManual", h))
+            .collect::<String>(),
+    )
+}
+
+pub fn get_dns() -> String {
+    format!(
+        r#"
+<GetDNS xmlns="{OVF_DEVICE}"/>
+"#,
+        OVF_DEVICE = OVF_DEVICE
+    )
+}
+
+pub fn set_dns(from_dhcp: bool, search_domains: &[String], manual: &[IpAddr]) -> String {
+    format!(
+        r#"
+<SetDNS xmlns="{OVF_DEVICE}">
+    <FromDHCP>{from_dhcp}</FromDHCP>{search_domains}{manual}
+</SetDNS>
+"#,
+        OVF_DEVICE = OVF_DEVICE,
+        from_dhcp = from_dhcp,
+        search_domains = search_domains
+            .iter()
+            .map(|d| optional_element("SearchDomain", Some(d)))
+            .collect::<String>(),
+        manual = manual
+            .iter()
+            .map(|a| ip_address("DNSManual", a))
+            .collect::<String>(),
+    )
+}
+
+pub fn get_network_interfaces() -> String {
+    format!(
+        r#"
+<GetNetworkInterfaces xmlns="{OVF_DEVICE}"/>
+"#,
+        OVF_DEVICE = OVF_DEVICE
+    )
+}
+
+/// `tt:PrefixedIPv4Address` or `tt:PrefixedIPv6Address` element
+fn prefixed_address<A: Display>(addr: &PrefixedAddress<A>) -> String {
+    format!(
+        r#"
+            <Manual>
+                <Address>{address}</Address>
+                <PrefixLength>{prefix_length}</PrefixLength>
+            </Manual>"#,
+        address = addr.address,
+        prefix_length = addr.prefix_length,
+    )
+}
+
+/// Optional child element of an IPv4 or IPv6 interface configuration
+fn optional_value(name: &str, value: Option<impl Display>) -> String {
+    value
+        .map(|v| format!("\n            <{name}>{v}</{name}>", name = name, v = v))
+        .unwrap_or_default()
+}
+
+pub fn set_network_interfaces(token: &str, setup: &NetworkInterfaceSetup) -> String {
+    let ipv4 = setup
+        .ipv4
+        .as_ref()
+        .map(|c| {
+            format!(
+                r#"
+        <IPv4 xmlns="{OVF_SCHEMA}">{enabled}{manual}{dhcp}
+        </IPv4>"#,
+                OVF_SCHEMA = OVF_SCHEMA,
+                enabled = optional_value("Enabled", c.enabled),
+                manual = c.manual.iter().map(prefixed_address).collect::<String>(),
+                dhcp = optional_value("DHCP", c.dhcp),
+            )
+        })
+        .unwrap_or_default();
+    let ipv6 = setup
+        .ipv6
+        .as_ref()
+        .map(|c| {
+            format!(
+                r#"
+        <IPv6 xmlns="{OVF_SCHEMA}">{enabled}{accept_router_advert}{manual}{dhcp}
+        </IPv6>"#,
+                OVF_SCHEMA = OVF_SCHEMA,
+                enabled = optional_value("Enabled", c.enabled),
+                accept_router_advert = optional_value("AcceptRouterAdvert", c.accept_router_advert),
+                manual = c.manual.iter().map(prefixed_address).collect::<String>(),
+                dhcp = optional_value("DHCP", c.dhcp),
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        r#"
+<SetNetworkInterfaces xmlns="{OVF_DEVICE}">
+    <InterfaceToken>{token}</InterfaceToken>
+    <NetworkInterface>{enabled}{mtu}{ipv4}{ipv6}
+    </NetworkInterface>
+</SetNetworkInterfaces>
+"#,
+        OVF_DEVICE = OVF_DEVICE,
+        token = escape(token),
+        enabled = setup
+            .enabled
+            .map(|e| format!(
+                "\n        <Enabled xmlns=\"{}\">{}</Enabled>",
+                OVF_SCHEMA, e
+            ))
+            .unwrap_or_default(),
+        mtu = setup
+            .mtu
+            .map(|m| format!("\n        <MTU xmlns=\"{}\">{}</MTU>", OVF_SCHEMA, m))
+            .unwrap_or_default(),
+        ipv4 = ipv4,
+        ipv6 = ipv6,
+    )
+}
+
+pub fn get_network_default_gateway() -> String {
+    format!(
+        r#"
+<GetNetworkDefaultGateway xmlns="{OVF_DEVICE}"/>
+"#,
+        OVF_DEVICE = OVF_DEVICE
+    )
+}
+
+pub fn set_network_default_gateway(gateway: &NetworkGateway) -> String {
+    format!(
+        r#"
+<SetNetworkDefaultGateway xmlns="{OVF_DEVICE}">{ipv4}{ipv6}
+</SetNetworkDefaultGateway>
+"#,
+        OVF_DEVICE = OVF_DEVICE,
+        ipv4 = gateway
+            .ipv4_address
+            .iter()
+            .map(|a| format!("\n    <IPv4Address>{}</IPv4Address>", a))
+            .collect::<String>(),
+        ipv6 = gateway
+            .ipv6_address
+            .iter()
+            .map(|a| format!("\n    <IPv6Address>{}</IPv6Address>", a))
+            .collect::<String>(),
+    )
+}
+
+pub fn get_nodes() -> String {
+    format!(
+        r#"
+<GetNodes xmlns="{OVF_PTZ}"/>
+"#,
+        OVF_PTZ = OVF_PTZ
+    )
+}
+
+pub fn get_node(node: &str) -> String {
     format!(
         r#"
 <GetNode xmlns="{OVF_PTZ}">
@@ -130,100 +443,148 @@ pub fn get_node(node: impl Display) -> String {
 </GetNode>
 "#,
         OVF_PTZ = OVF_PTZ,
-        node = node
+        node = escape(node)
     )
 }
 
-pub fn relative_mode(profile: impl Display, x: f32, y: f32, xspeed: f32, yspeed: f32) -> String {
+pub fn get_configurations() -> String {
     format!(
         r#"
-<RelativeMove xmlns="{OVF_PTZ}">
-    <ProfileToken>{profile}</ProfileToken>
-    <Translation>
-        <PanTilt x="{x}" y="{y}" space="{OVF_PTS_TGS}" xmlns="{OVF_SCHEMA}"/>
-    </Translation>
-    <Speed>
-        <PanTilt x="{xspeed}" y="{yspeed}" space="{OVF_PTS_GSS}" xmlns="{OVF_SCHEMA}"/>
-    </Speed>
-</RelativeMove>
+<GetConfigurations xmlns="{OVF_PTZ}"/>
+"#,
+        OVF_PTZ = OVF_PTZ
+    )
+}
+
+pub fn get_configuration_options(configuration: &str) -> String {
+    format!(
+        r#"
+<GetConfigurationOptions xmlns="{OVF_PTZ}">
+    <ConfigurationToken>{configuration}</ConfigurationToken>
+</GetConfigurationOptions>
 "#,
         OVF_PTZ = OVF_PTZ,
-        profile = profile,
-        x = x,
-        y = y,
-        OVF_PTS_TGS = OVF_PTS_TGS,
-        OVF_SCHEMA = OVF_SCHEMA,
-        xspeed = xspeed,
-        yspeed = yspeed,
-        OVF_PTS_GSS = OVF_PTS_GSS,
+        configuration = escape(configuration)
     )
 }
 
-pub fn relative_move_zoom(profile: impl Display, z: f32, zspeed: f32) -> String {
+/// Optional `Speed` element of the move requests
+fn speed(speed: Option<&PtzVector>) -> String {
+    speed
+        .map(|s| {
+            format!(
+                "\n    <Speed>{}\n    </Speed>",
+                ptz_vector(s, Some(OVF_PTS_GSS), Some(OVF_ZS_ZGSS))
+            )
+        })
+        .unwrap_or_default()
+}
+
+pub fn relative_move(
+    profile: &str,
+    translation: &PtzVector,
+    pan_tilt_space: &str,
+    speed: Option<&PtzVector>,
+) -> String {
     format!(
         r#"
 <RelativeMove xmlns="{OVF_PTZ}">
     <ProfileToken>{profile}</ProfileToken>
-    <Translation>
-        <Zoom x="{z}" space="{OVF_ZS_TGS}" xmlns="{OVF_SCHEMA}"/>
-    </Translation>
-    <Speed>
-        <Zoom x="{zspeed}" space="{OVF_ZS_ZGSS}" xmlns="{OVF_SCHEMA}"/>
-    </Speed>
+    <Translation>{translation}
+    </Translation>{speed}
 </RelativeMove>
 "#,
         OVF_PTZ = OVF_PTZ,
-        profile = profile,
-        z = z,
-        OVF_ZS_TGS = OVF_ZS_TGS,
-        OVF_SCHEMA = OVF_SCHEMA,
-        zspeed = zspeed,
-        OVF_ZS_ZGSS = OVF_ZS_ZGSS,
+        profile = escape(profile),
+        translation = ptz_vector(translation, Some(pan_tilt_space), Some(OVF_ZS_TGS)),
+        speed = self::speed(speed),
     )
 }
 
-pub fn absolute_move(profile: impl Display, x: f32, y: f32, z: f32) -> String {
+pub fn absolute_move(profile: &str, position: &PtzVector, speed: Option<&PtzVector>) -> String {
     format!(
         r#"
 <AbsoluteMove xmlns="{OVF_PTZ}">
-    <Position>
-        <ProfileToken>{profile}</ProfileToken>
-        <PanTilt x="{x}" y="{y}" space="{OVF_PTS_PGS}" xmlns="{OVF_SCHEMA}"/>
-        <Zoom x="{z}" space="{OVF_ZS_PGS}" xmlns="{OVF_SCHEMA}"/>
-    </Position>
+    <ProfileToken>{profile}</ProfileToken>
+    <Position>{position}
+    </Position>{speed}
 </AbsoluteMove>
 "#,
         OVF_PTZ = OVF_PTZ,
-        profile = profile,
-        x = x,
-        y = y,
-        OVF_PTS_PGS = OVF_PTS_PGS,
-        OVF_SCHEMA = OVF_SCHEMA,
-        z = z,
-        OVF_ZS_PGS = OVF_ZS_PGS,
+        profile = escape(profile),
+        position = ptz_vector(position, Some(OVF_PTS_PGS), Some(OVF_ZS_PGS)),
+        speed = self::speed(speed),
     )
 }
 
-pub fn continuous_move(profile: impl Display, x: f32, y: f32) -> String {
+/// Format an `xs:duration` value, with a millisecond resolution
+pub fn duration(d: Duration) -> String {
+    match d.subsec_millis() {
+        0 => format!("PT{}S", d.as_secs()),
+        millis => format!(
+            "PT{}.{}S",
+            d.as_secs(),
+            format!("{:03}", millis).trim_end_matches('0')
+        ),
+    }
+}
+
+/// Optional `Timeout` element of ContinuousMove
+fn timeout(timeout: Option<Duration>) -> String {
+    timeout
+        .map(|t| format!("\n    <Timeout>{}</Timeout>", duration(t)))
+        .unwrap_or_default()
+}
+
+/// Elements of a `tt:PTZVector` or `tt:PTZSpeed`. The `space` attributes are
+/// omitted when None, letting the camera use its default spaces.
+fn ptz_vector(
+    vector: &PtzVector,
+    pan_tilt_space: Option<&str>,
+    zoom_space: Option<&str>,
+) -> String {
+    let space = |s: Option<&str>| s.map(|s| format!(r#" space="{}""#, s)).unwrap_or_default();
+
+    let mut elements = String::new();
+    if let Some((x, y)) = vector.pan_tilt {
+        elements += &format!(
+            r#"
+        <PanTilt x="{x}" y="{y}"{space} xmlns="{OVF_SCHEMA}"/>"#,
+            x = x,
+            y = y,
+            space = space(pan_tilt_space),
+            OVF_SCHEMA = OVF_SCHEMA,
+        );
+    }
+    if let Some(z) = vector.zoom {
+        elements += &format!(
+            r#"
+        <Zoom x="{z}"{space} xmlns="{OVF_SCHEMA}"/>"#,
+            z = z,
+            space = space(zoom_space),
+            OVF_SCHEMA = OVF_SCHEMA,
+        );
+    }
+    elements
+}
+
+pub fn continuous_move(profile: &str, velocity: &PtzVector, timeout: Option<Duration>) -> String {
     format!(
         r#"
 <ContinuousMove xmlns="{OVF_PTZ}">
     <ProfileToken>{profile}</ProfileToken>
-    <Velocity>
-        <PanTilt x="{x}" y="{y}" space="{OVF_PTS_VGS}" xmlns="{OVF_SCHEMA}"/>
-    </Velocity>
+    <Velocity>{velocity}
+    </Velocity>{timeout}
 </ContinuousMove>
 "#,
         OVF_PTZ = OVF_PTZ,
-        profile = profile,
-        x = x,
-        y = y,
-        OVF_PTS_VGS = OVF_PTS_VGS,
-        OVF_SCHEMA = OVF_SCHEMA
+        profile = escape(profile),
+        velocity = ptz_vector(velocity, Some(OVF_PTS_VGS), Some(OVF_ZS_VGS)),
+        timeout = self::timeout(timeout),
     )
 }
 
-pub fn stop(profile: impl Display, ptstop: bool, zstop: bool) -> String {
+pub fn stop(profile: &str, ptstop: bool, zstop: bool) -> String {
     format!(
         r#"
 <Stop xmlns="{OVF_PTZ}">
@@ -233,45 +594,49 @@ pub fn stop(profile: impl Display, ptstop: bool, zstop: bool) -> String {
 </Stop>
 "#,
         OVF_PTZ = OVF_PTZ,
-        profile = profile,
+        profile = escape(profile),
         ptstop = ptstop,
         zstop = zstop,
     )
 }
 
-pub fn continuous_move_zoom(profile: impl Display, z: f32) -> String {
-    format!(
-        r#"
-<ContinuousMove xmlns="{OVF_PTZ}">
-    <ProfileToken>{profile}</ProfileToken>
-    <Velocity>
-        <Zoom x="{z}" space="{OVF_ZS_VGS}" xmlns="{OVF_SCHEMA}"/>
-    </Velocity>
-</ContinuousMove>
-"#,
-        OVF_PTZ = OVF_PTZ,
-        profile = profile,
-        z = z,
-        OVF_ZS_VGS = OVF_ZS_VGS,
-        OVF_SCHEMA = OVF_SCHEMA
-    )
+/// Optional element with escaped text content
+fn optional_element(name: &str, value: Option<&str>) -> String {
+    value
+        .map(|v| {
+            format!(
+                "\n    <{name}>{value}</{name}>",
+                name = name,
+                value = escape(v)
+            )
+        })
+        .unwrap_or_default()
 }
 
-pub fn set_preset(profile: impl Display, preset: u32) -> String {
+/// Escape the XML special characters of a text content
+pub fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+pub fn set_preset(profile: &str, name: Option<&str>, token: Option<&str>) -> String {
     format!(
         r#"
-    <SetPreset xmlns="{OVF_PTZ}">
-        <ProfileToken>{profile}</ProfileToken>
-        <PresetName>{preset}</PresetName>
-    </SetPreset>
+<SetPreset xmlns="{OVF_PTZ}">
+    <ProfileToken>{profile}</ProfileToken>{name}{token}
+</SetPreset>
 "#,
         OVF_PTZ = OVF_PTZ,
-        profile = profile,
-        preset = preset,
+        profile = escape(profile),
+        name = optional_element("PresetName", name),
+        token = optional_element("PresetToken", token),
     )
 }
 
-pub fn get_presets(profile: impl Display) -> String {
+pub fn get_presets(profile: &str) -> String {
     format!(
         r#"
 <GetPresets xmlns="{OVF_PTZ}">
@@ -279,39 +644,26 @@ pub fn get_presets(profile: impl Display) -> String {
 </GetPresets>
 "#,
         OVF_PTZ = OVF_PTZ,
-        profile = profile
+        profile = escape(profile)
     )
 }
 
-pub fn goto_preset(
-    profile: impl Display,
-    preset: u32,
-    xspeed: f32,
-    yspeed: f32,
-    zspeed: f32,
-) -> String {
+pub fn goto_preset(profile: &str, preset: &str, speed: Option<&PtzVector>) -> String {
     format!(
         r#"
 <GotoPreset xmlns="{OVF_PTZ}">
     <ProfileToken>{profile}</ProfileToken>
-    <PresetToken>{preset}</PresetToken>
-    <Speed>
-        <PanTilt x="{xspeed}" y="{yspeed}" xmlns="{OVF_SCHEMA}"/>
-        <Zoom x="{zspeed}" xmlns="{OVF_SCHEMA}"/>
-    </Speed>
+    <PresetToken>{preset}</PresetToken>{speed}
 </GotoPreset>
 "#,
         OVF_PTZ = OVF_PTZ,
-        profile = profile,
-        preset = preset,
-        xspeed = xspeed,
-        yspeed = yspeed,
-        OVF_SCHEMA = OVF_SCHEMA,
-        zspeed = zspeed,
+        profile = escape(profile),
+        preset = escape(preset),
+        speed = self::speed(speed),
     )
 }
 
-pub fn remove_preset(profile: impl Display, preset: impl Display) -> String {
+pub fn remove_preset(profile: &str, preset: &str) -> String {
     format!(
         r#"
 <RemovePreset xmlns="{OVF_PTZ}">
@@ -320,8 +672,183 @@ pub fn remove_preset(profile: impl Display, preset: impl Display) -> String {
 </RemovePreset>
 "#,
         OVF_PTZ = OVF_PTZ,
-        profile = profile,
-        preset = preset
+        profile = escape(profile),
+        preset = escape(preset)
+    )
+}
+
+pub fn goto_home_position(profile: &str, speed: Option<&PtzVector>) -> String {
+    format!(
+        r#"
+<GotoHomePosition xmlns="{OVF_PTZ}">
+    <ProfileToken>{profile}</ProfileToken>{speed}
+</GotoHomePosition>
+"#,
+        OVF_PTZ = OVF_PTZ,
+        profile = escape(profile),
+        speed = self::speed(speed),
+    )
+}
+
+pub fn set_home_position(profile: &str) -> String {
+    format!(
+        r#"
+<SetHomePosition xmlns="{OVF_PTZ}">
+    <ProfileToken>{profile}</ProfileToken>
+</SetHomePosition>
+"#,
+        OVF_PTZ = OVF_PTZ,
+        profile = escape(profile)
+    )
+}
+
+pub fn send_auxiliary_command(profile: &str, command: &str) -> String {
+    format!(
+        r#"
+<SendAuxiliaryCommand xmlns="{OVF_PTZ}">
+    <ProfileToken>{profile}</ProfileToken>
+    <AuxiliaryData>{command}</AuxiliaryData>
+</SendAuxiliaryCommand>
+"#,
+        OVF_PTZ = OVF_PTZ,
+        profile = escape(profile),
+        command = escape(command),
+    )
+}
+
+pub fn get_preset_tours(profile: &str) -> String {
+    format!(
+        r#"
+<GetPresetTours xmlns="{OVF_PTZ}">
+    <ProfileToken>{profile}</ProfileToken>
+</GetPresetTours>
+"#,
+        OVF_PTZ = OVF_PTZ,
+        profile = escape(profile)
+    )
+}
+
+pub fn get_preset_tour_options(profile: &str, tour: Option<&str>) -> String {
+    format!(
+        r#"
+<GetPresetTourOptions xmlns="{OVF_PTZ}">
+    <ProfileToken>{profile}</ProfileToken>{tour}
+</GetPresetTourOptions>
+"#,
+        OVF_PTZ = OVF_PTZ,
+        profile = escape(profile),
+        tour = optional_element("PresetTourToken", tour),
+    )
+}
+
+pub fn create_preset_tour(profile: &str) -> String {
+    format!(
+        r#"
+<CreatePresetTour xmlns="{OVF_PTZ}">
+    <ProfileToken>{profile}</ProfileToken>
+</CreatePresetTour>
+"#,
+        OVF_PTZ = OVF_PTZ,
+        profile = escape(profile)
+    )
+}
+
+/// `TourSpot` element of a preset tour
+fn tour_spot(spot: &TourSpot) -> String {
+    format!(
+        r#"
+        <TourSpot xmlns="{OVF_SCHEMA}">
+            <PresetDetail><PresetToken>{preset}</PresetToken></PresetDetail>{speed}{stay_time}
+        </TourSpot>"#,
+        OVF_SCHEMA = OVF_SCHEMA,
+        preset = escape(&spot.preset_token),
+        speed = speed(spot.speed.as_ref()),
+        stay_time = spot
+            .stay_time
+            .map(|t| format!("\n    <StayTime>{}</StayTime>", duration(t)))
+            .unwrap_or_default(),
+    )
+}
+
+pub fn modify_preset_tour(profile: &str, tour: &PresetTour) -> String {
+    let condition = &tour.starting_condition;
+
+    format!(
+        r#"
+<ModifyPresetTour xmlns="{OVF_PTZ}">
+    <ProfileToken>{profile}</ProfileToken>
+    <PresetTour token="{token}">
+        <Name xmlns="{OVF_SCHEMA}">{name}</Name>
+        <Status xmlns="{OVF_SCHEMA}"><State>{state}</State></Status>
+        <AutoStart xmlns="{OVF_SCHEMA}">{auto_start}</AutoStart>
+        <StartingCondition RandomPresetOrder="{random}" xmlns="{OVF_SCHEMA}">{recurring_time}{recurring_duration}{direction}
+        </StartingCondition>{spots}
+    </PresetTour>
+</ModifyPresetTour>
+"#,
+        OVF_PTZ = OVF_PTZ,
+        OVF_SCHEMA = OVF_SCHEMA,
+        profile = escape(profile),
+        token = escape(&tour.token),
+        name = escape(&tour.name),
+        state = tour.state,
+        auto_start = tour.auto_start,
+        random = condition.random_preset_order,
+        recurring_time = optional_element(
+            "RecurringTime",
+            condition.recurring_time.map(|t| t.to_string()).as_deref()
+        ),
+        recurring_duration = optional_element(
+            "RecurringDuration",
+            condition.recurring_duration.map(duration).as_deref()
+        ),
+        direction = optional_element(
+            "Direction",
+            condition.direction.map(|d| d.to_string()).as_deref()
+        ),
+        spots = tour.spots.iter().map(tour_spot).collect::<String>(),
+    )
+}
+
+pub fn operate_preset_tour(profile: &str, tour: &str, operation: TourOperation) -> String {
+    format!(
+        r#"
+<OperatePresetTour xmlns="{OVF_PTZ}">
+    <ProfileToken>{profile}</ProfileToken>
+    <PresetTourToken>{tour}</PresetTourToken>
+    <Operation>{operation}</Operation>
+</OperatePresetTour>
+"#,
+        OVF_PTZ = OVF_PTZ,
+        profile = escape(profile),
+        tour = escape(tour),
+        operation = operation,
+    )
+}
+
+pub fn remove_preset_tour(profile: &str, tour: &str) -> String {
+    format!(
+        r#"
+<RemovePresetTour xmlns="{OVF_PTZ}">
+    <ProfileToken>{profile}</ProfileToken>
+    <PresetTourToken>{tour}</PresetTourToken>
+</RemovePresetTour>
+"#,
+        OVF_PTZ = OVF_PTZ,
+        profile = escape(profile),
+        tour = escape(tour)
+    )
+}
+
+pub fn get_status(profile: &str) -> String {
+    format!(
+        r#"
+<GetStatus xmlns="{OVF_PTZ}">
+    <ProfileToken>{profile}</ProfileToken>
+</GetStatus>
+"#,
+        OVF_PTZ = OVF_PTZ,
+        profile = escape(profile)
     )
 }
 
@@ -334,20 +861,33 @@ pub fn get_video_sources() -> String {
     )
 }
 
-pub fn get_stream_uri(profile: impl Display) -> String {
+pub fn get_stream_uri(profile: &str, stream: impl Display, protocol: impl Display) -> String {
     format!(
         r#"
 <GetStreamUri xmlns="{OVF_MEDIA}">
     <StreamSetup>
-        <Stream xmlns="{OVF_SCHEMA}">RTP-Unicast</Stream>
+        <Stream xmlns="{OVF_SCHEMA}">{stream}</Stream>
         <Transport xmlns="{OVF_SCHEMA}">
-            <Protocol>UDP</Protocol>
+            <Protocol>{protocol}</Protocol>
         </Transport>
     </StreamSetup>
     <ProfileToken>{profile}</ProfileToken>
 </GetStreamUri>"#,
         OVF_MEDIA = OVF_MEDIA,
         OVF_SCHEMA = OVF_SCHEMA,
-        profile = profile
+        stream = stream,
+        protocol = protocol,
+        profile = escape(profile)
+    )
+}
+
+pub fn get_snapshot_uri(profile: &str) -> String {
+    format!(
+        r#"
+<GetSnapshotUri xmlns="{OVF_MEDIA}">
+    <ProfileToken>{profile}</ProfileToken>
+</GetSnapshotUri>"#,
+        OVF_MEDIA = OVF_MEDIA,
+        profile = escape(profile)
     )
 }
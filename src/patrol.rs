@@ -0,0 +1,163 @@
+//! Client-side patrol over presets, for cameras without preset tours
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::camera::OnvifCamera;
+use crate::error::Result;
+use crate::ptz::PtzVector;
+
+/// Stop of a patrol
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatrolStop {
+    pub preset_token: String,
+    /// Speed of the move to the preset, the camera default if None
+    pub speed: Option<PtzVector>,
+    /// Time before moving to the next stop, counted from the move request so
+    /// it must include the travel time
+    pub dwell: Duration,
+}
+
+/// Result of a patrol step, passed to the callback of `Patrol::start()`
+#[derive(Debug)]
+pub struct PatrolStep {
+    /// Index of the stop in the patrol
+    pub index: usize,
+    pub preset_token: String,
+    /// Result of the GotoPreset request
+    pub result: Result<()>,
+}
+
+/// Cancellation flag shared with the patrol thread
+type CancelFlag = Arc<(Mutex<bool>, Condvar)>;
+
+/// Handle of a patrol running on a background thread. The patrol is cancelled
+/// when the handle is dropped.
+///
+/// Example:
+///
+/// ```ignored
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use simpleonvif::{OnvifCamera, Patrol, PatrolStop};
+///
+/// let cam = Arc::new(OnvifCamera::new("http://192.168.0.32:8080", Some("profile1"))?);
+/// let stops = ["1", "2"]
+///     .iter()
+///     .map(|token| PatrolStop {
+///         preset_token: token.to_string(),
+///         speed: None,
+///         dwell: Duration::from_secs(20),
+///     })
+///     .collect();
+///
+/// let patrol = Patrol::start(cam, stops, |step| println!("{:?}", step));
+/// // ...
+/// patrol.cancel()?;
+/// ```
+#[derive(Debug)]
+pub struct Patrol {
+    camera: Arc<OnvifCamera>,
+    cancel: CancelFlag,
+    thread: Option<JoinHandle<Result<()>>>,
+}
+
+impl Patrol {
+    /// Start cycling through the stops until cancelled. Each move to a preset
+    /// is reported to `on_step`, a failed move does not stop the patrol.
+    pub fn start<F>(camera: Arc<OnvifCamera>, stops: Vec<PatrolStop>, on_step: F) -> Self
+    where
+        F: FnMut(PatrolStep) + Send + 'static,
+    {
+        let cancel: CancelFlag = Arc::new((Mutex::new(false), Condvar::new()));
+        let flag = Arc::clone(&cancel);
+        let thread_camera = Arc::clone(&camera);
+        let thread = thread::spawn(move || run(&thread_camera, &stops, on_step, &flag));
+
+        Self {
+            camera,
+            cancel,
+            thread: Some(thread),
+        }
+    }
+
+    /// True while the patrol thread runs. False once the patrol is cancelled,
+    /// if it has no stops, or if the thread panicked.
+    pub fn is_running(&self) -> bool {
+        self.thread.as_ref().is_some_and(|t| !t.is_finished())
+    }
+
+    /// Cancel the patrol and wait for the thread, which stops the camera
+    /// movement. The camera is also stopped if the thread panicked, for
+    /// example in the step callback. Returns the result of the Stop request.
+    pub fn cancel(mut self) -> Result<()> {
+        self.shutdown()
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        let (lock, cvar) = &*self.cancel;
+        *lock.lock().unwrap() = true;
+        cvar.notify_all();
+
+        match self.thread.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(_)) => {
+                error!("patrol thread panicked, stopping the camera");
+                self.camera.stop(true, true)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Patrol {
+    fn drop(&mut self) {
+        if let Err(e) = self.shutdown() {
+            warn!("cannot stop the camera at the end of the patrol: {}", e);
+        }
+    }
+}
+
+/// Wait for `timeout` and return true if the patrol has been cancelled
+fn wait_cancel(cancel: &CancelFlag, timeout: Duration) -> bool {
+    let (lock, cvar) = &**cancel;
+    let guard = lock.lock().unwrap();
+    let (cancelled, _) = cvar
+        .wait_timeout_while(guard, timeout, |cancelled| !*cancelled)
+        .unwrap();
+    *cancelled
+}
+
+/// Body of the patrol thread
+fn run<F>(
+    camera: &OnvifCamera,
+    stops: &[PatrolStop],
+    mut on_step: F,
+    cancel: &CancelFlag,
+) -> Result<()>
+where
+    F: FnMut(PatrolStep),
+{
+    if stops.is_empty() {
+        return Ok(());
+    }
+
+    for (index, stop) in stops.iter().enumerate().cycle() {
+        trace!("patrol step {} preset={}", index, stop.preset_token);
+
+        let result = camera.goto_preset(&stop.preset_token, stop.speed.as_ref());
+        on_step(PatrolStep {
+            index,
+            preset_token: stop.preset_token.clone(),
+            result,
+        });
+
+        if wait_cancel(cancel, stop.dwell) {
+            break;
+        }
+    }
+
+    debug!("patrol cancelled, stopping the camera");
+    camera.stop(true, true)
+}
@@ -0,0 +1,591 @@
+//! PTZ types and responses
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use roxmltree::Node;
+
+use crate::error::{Error, Result};
+use crate::namespaces::{OVF_PTS_GSS, OVF_PTS_TGS, OVF_PTS_TSFOV, OVF_ZS_PGS, OVF_ZS_ZGSS};
+use crate::xml;
+
+/// Pan-tilt and zoom values of a move. Only the present components are sent,
+/// so that any combination is done with a single request.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PtzVector {
+    /// Horizontal and vertical values
+    pub pan_tilt: Option<(f32, f32)>,
+    pub zoom: Option<f32>,
+}
+
+impl PtzVector {
+    /// Vector with pan and tilt only
+    pub fn pan_tilt(x: f32, y: f32) -> Self {
+        Self {
+            pan_tilt: Some((x, y)),
+            zoom: None,
+        }
+    }
+
+    /// Vector with zoom only
+    pub fn zoom(z: f32) -> Self {
+        Self {
+            pan_tilt: None,
+            zoom: Some(z),
+        }
+    }
+
+    /// Vector with pan, tilt and zoom
+    pub fn pan_tilt_zoom(x: f32, y: f32, z: f32) -> Self {
+        Self {
+            pan_tilt: Some((x, y)),
+            zoom: Some(z),
+        }
+    }
+
+    /// True if the vector has neither pan-tilt nor zoom
+    pub fn is_empty(&self) -> bool {
+        self.pan_tilt.is_none() && self.zoom.is_none()
+    }
+
+    /// True if all the components of `target` are present in this vector and
+    /// within `tolerance` of it. An empty target is never reached.
+    pub fn is_near(&self, target: &PtzVector, tolerance: f32) -> bool {
+        let near = |a: f32, b: f32| (a - b).abs() <= tolerance;
+        let pan_tilt = match (target.pan_tilt, self.pan_tilt) {
+            (None, _) => true,
+            (Some((tx, ty)), Some((x, y))) => near(tx, x) && near(ty, y),
+            (Some(_), None) => false,
+        };
+        let zoom = match (target.zoom, self.zoom) {
+            (None, _) => true,
+            (Some(tz), Some(z)) => near(tz, z),
+            (Some(_), None) => false,
+        };
+
+        !target.is_empty() && pan_tilt && zoom
+    }
+}
+
+/// PTZ preset, a saved position of the camera
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preset {
+    pub token: String,
+    pub name: String,
+    /// Saved position, if reported by the camera
+    pub position: Option<PtzVector>,
+}
+
+/// Movement state of the pan-tilt or zoom axes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveStatus {
+    Idle,
+    Moving,
+    /// State reported by the camera but not known
+    Unknown,
+}
+
+impl MoveStatus {
+    fn parse(s: &str) -> Self {
+        match s {
+            "IDLE" => MoveStatus::Idle,
+            "MOVING" => MoveStatus::Moving,
+            _ => MoveStatus::Unknown,
+        }
+    }
+}
+
+/// PTZ status of a profile, returned by GetStatus
+#[derive(Debug, Clone, PartialEq)]
+pub struct PtzStatus {
+    /// Current position, the components not reported by the camera are None
+    pub position: PtzVector,
+    /// URI of the space of the pan-tilt position
+    pub pan_tilt_space: Option<String>,
+    /// URI of the space of the zoom position
+    pub zoom_space: Option<String>,
+    /// State of the pan-tilt axes, None if not reported by the camera
+    pub pan_tilt_status: Option<MoveStatus>,
+    /// State of the zoom axis, None if not reported by the camera
+    pub zoom_status: Option<MoveStatus>,
+    /// Error reported by the PTZ unit, if any
+    pub error: Option<String>,
+    /// Camera time of the status
+    pub utc_time: Option<DateTime<Utc>>,
+}
+
+impl PtzStatus {
+    /// True if every axis reported by the camera is IDLE, and at least one
+    /// is reported. An axis reported as UNKNOWN is not considered idle, an
+    /// axis not reported at all is ignored, e.g. the zoom of a pan-tilt only
+    /// camera.
+    pub fn is_idle(&self) -> bool {
+        let reported = [self.pan_tilt_status, self.zoom_status];
+        reported.iter().any(Option::is_some)
+            && reported
+                .iter()
+                .flatten()
+                .all(|status| *status == MoveStatus::Idle)
+    }
+
+    /// True if pan-tilt or zoom reports MOVING
+    pub fn is_moving(&self) -> bool {
+        self.pan_tilt_status == Some(MoveStatus::Moving)
+            || self.zoom_status == Some(MoveStatus::Moving)
+    }
+}
+
+/// Parameters of the methods waiting for the end of a move. The default polls
+/// every 200 ms for up to 30 s, with a tolerance of 0.01.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaitSetup {
+    /// Delay between two GetStatus requests
+    pub poll_interval: Duration,
+    /// Maximum duration of the wait, `Error::MoveTimeout` is returned after it
+    pub timeout: Duration,
+    /// Maximum difference between the position and the target for the move to
+    /// be considered complete, even if the camera still reports MOVING
+    pub tolerance: f32,
+}
+
+impl Default for WaitSetup {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(200),
+            timeout: Duration::from_secs(30),
+            tolerance: 0.01,
+        }
+    }
+}
+
+/// Number of consecutive IDLE statuses after which a move is considered
+/// complete even if the camera never reported MOVING, for the cameras not
+/// reporting it or moves too short to be seen between two polls
+const IDLE_POLLS_WITHOUT_MOVING: u32 = 5;
+
+/// Progress of a move, updated with each polled status
+#[derive(Debug)]
+pub(crate) struct MoveProgress<'a> {
+    target: Option<&'a PtzVector>,
+    tolerance: f32,
+    moving_seen: bool,
+    idle_polls: u32,
+}
+
+impl<'a> MoveProgress<'a> {
+    pub(crate) fn new(target: Option<&'a PtzVector>, tolerance: f32) -> Self {
+        Self {
+            target,
+            tolerance,
+            moving_seen: false,
+            idle_polls: 0,
+        }
+    }
+
+    /// Record `status` and return true if the move is complete. The camera
+    /// must report IDLE after MOVING, since a camera that has not started
+    /// moving yet still reports IDLE, or report IDLE for
+    /// `IDLE_POLLS_WITHOUT_MOVING` consecutive polls. With a target, a
+    /// position within the tolerance of it is enough.
+    pub(crate) fn update(&mut self, status: &PtzStatus) -> bool {
+        self.moving_seen |= status.is_moving();
+        self.idle_polls = if status.is_idle() {
+            self.idle_polls + 1
+        } else {
+            0
+        };
+        let stopped = self.idle_polls > 0
+            && (self.moving_seen || self.idle_polls >= IDLE_POLLS_WITHOUT_MOVING);
+        match self.target {
+            Some(target) => status.position.is_near(target, self.tolerance) || stopped,
+            None => stopped,
+        }
+    }
+}
+
+/// Pan-tilt space of the translations of relative moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranslationSpace {
+    /// Generic space, the values are in [-1, 1] relative to the full range
+    Generic,
+    /// Field of view space, the values are in [-1, 1] relative to the current
+    /// field of view, so that the point at (x, y) in the image gets centered
+    Fov,
+}
+
+impl TranslationSpace {
+    /// URI of the space
+    pub fn uri(self) -> &'static str {
+        match self {
+            TranslationSpace::Generic => OVF_PTS_TGS,
+            TranslationSpace::Fov => OVF_PTS_TSFOV,
+        }
+    }
+}
+
+/// Range of float values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl FloatRange {
+    /// Range of the generic spaces, except positions of zoom and speeds
+    const GENERIC: FloatRange = FloatRange {
+        min: -1.0,
+        max: 1.0,
+    };
+    /// Range of the generic zoom position space and of the speed spaces
+    const GENERIC_POSITIVE: FloatRange = FloatRange { min: 0.0, max: 1.0 };
+
+    /// Check that the value is in the range, clamping it or returning
+    /// `Error::OutOfRange` according to the policy. NaN and infinite values
+    /// are always rejected.
+    pub fn check(&self, value: f32, policy: RangePolicy) -> Result<f32> {
+        if value.is_finite() && self.min <= value && value <= self.max {
+            return Ok(value);
+        }
+        match policy {
+            RangePolicy::Clamp if value.is_finite() => Ok(value.clamp(self.min, self.max)),
+            _ => Err(Error::OutOfRange {
+                value,
+                range: *self,
+            }),
+        }
+    }
+}
+
+/// Behaviour of the moves when a value is out of the range of its space
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RangePolicy {
+    /// Return `Error::OutOfRange` without sending the request
+    #[default]
+    Reject,
+    /// Replace the value by the nearest bound
+    Clamp,
+}
+
+/// Coordinate space, with the range of the X axis and of the Y axis for
+/// two-dimensional (pan-tilt) spaces
+#[derive(Debug, Clone, PartialEq)]
+pub struct Space {
+    /// URI of the space
+    pub uri: String,
+    pub x_range: FloatRange,
+    pub y_range: Option<FloatRange>,
+}
+
+/// PTZ configuration of a media profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct PtzConfiguration {
+    pub token: String,
+    pub name: String,
+    /// Token of the PTZ node controlled by this configuration
+    pub node_token: String,
+    /// Default timeout of continuous moves
+    pub default_timeout: Option<Duration>,
+    /// Pan-tilt position limits
+    pub pan_tilt_limits: Option<Space>,
+    /// Zoom position limits
+    pub zoom_limits: Option<Space>,
+}
+
+/// Coordinate spaces supported by a PTZ node, for each kind of move. An empty
+/// list means that the move is not supported on this axis.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PtzSpaces {
+    pub absolute_pan_tilt_position: Vec<Space>,
+    pub absolute_zoom_position: Vec<Space>,
+    pub relative_pan_tilt_translation: Vec<Space>,
+    pub relative_zoom_translation: Vec<Space>,
+    pub continuous_pan_tilt_velocity: Vec<Space>,
+    pub continuous_zoom_velocity: Vec<Space>,
+    pub pan_tilt_speed: Vec<Space>,
+    pub zoom_speed: Vec<Space>,
+}
+
+impl PtzSpaces {
+    /// True if any kind of pan-tilt move is supported
+    pub fn supports_pan_tilt(&self) -> bool {
+        !(self.absolute_pan_tilt_position.is_empty()
+            && self.relative_pan_tilt_translation.is_empty()
+            && self.continuous_pan_tilt_velocity.is_empty())
+    }
+
+    /// Find the space with the given URI
+    pub fn find(&self, uri: &str) -> Option<&Space> {
+        [
+            &self.absolute_pan_tilt_position,
+            &self.absolute_zoom_position,
+            &self.relative_pan_tilt_translation,
+            &self.relative_zoom_translation,
+            &self.continuous_pan_tilt_velocity,
+            &self.continuous_zoom_velocity,
+            &self.pan_tilt_speed,
+            &self.zoom_speed,
+        ]
+        .iter()
+        .flat_map(|spaces| spaces.iter())
+        .find(|s| s.uri == uri)
+    }
+
+    /// True if any kind of zoom move is supported
+    pub fn supports_zoom(&self) -> bool {
+        !(self.absolute_zoom_position.is_empty()
+            && self.relative_zoom_translation.is_empty()
+            && self.continuous_zoom_velocity.is_empty())
+    }
+}
+
+/// PTZ node, a physical PTZ unit of the device
+#[derive(Debug, Clone, PartialEq)]
+pub struct PtzNode {
+    pub token: String,
+    pub name: String,
+    /// True if the home position cannot be changed with `set_home_position()`,
+    /// None if not reported
+    pub fixed_home_position: Option<bool>,
+    pub supported_spaces: PtzSpaces,
+    pub maximum_number_of_presets: u32,
+    /// True if the node supports the home position
+    pub home_supported: bool,
+    /// Auxiliary commands supported by the node, such as "tt:Wiper|On"
+    pub auxiliary_commands: Vec<String>,
+}
+
+/// Valid values for a PTZ configuration, returned by GetConfigurationOptions
+#[derive(Debug, Clone, PartialEq)]
+pub struct PtzConfigurationOptions {
+    pub spaces: PtzSpaces,
+    /// Minimum and maximum continuous move timeouts
+    pub timeout_range: Option<(Duration, Duration)>,
+}
+
+/// Default range of the values of a space
+fn default_range(uri: &str) -> FloatRange {
+    match uri {
+        OVF_ZS_PGS | OVF_PTS_GSS | OVF_ZS_ZGSS => FloatRange::GENERIC_POSITIVE,
+        _ => FloatRange::GENERIC,
+    }
+}
+
+/// Check the components of a vector sent in the given spaces, against the
+/// ranges of `spaces` if known or else the ranges of the generic spaces
+pub(crate) fn check_vector(
+    vector: &PtzVector,
+    pan_tilt_space: &str,
+    zoom_space: &str,
+    spaces: Option<&PtzSpaces>,
+    policy: RangePolicy,
+) -> Result<PtzVector> {
+    let ranges = |uri| match spaces.and_then(|s| s.find(uri)) {
+        Some(space) => (space.x_range, space.y_range.unwrap_or(space.x_range)),
+        None => (default_range(uri), default_range(uri)),
+    };
+
+    let pan_tilt = match vector.pan_tilt {
+        Some((x, y)) => {
+            let (x_range, y_range) = ranges(pan_tilt_space);
+            Some((x_range.check(x, policy)?, y_range.check(y, policy)?))
+        }
+        None => None,
+    };
+    let zoom = match vector.zoom {
+        Some(z) => Some(ranges(zoom_space).0.check(z, policy)?),
+        None => None,
+    };
+
+    Ok(PtzVector { pan_tilt, zoom })
+}
+
+/// Parse a `tt:PTZVector` element, the spaces are ignored
+pub(crate) fn parse_vector(node: Node) -> PtzVector {
+    let attr = |n: Node, name| n.attribute(name).and_then(|v| v.trim().parse().ok());
+
+    PtzVector {
+        pan_tilt: xml::child(node, "PanTilt").and_then(|n| Some((attr(n, "x")?, attr(n, "y")?))),
+        zoom: xml::child(node, "Zoom").and_then(|n| attr(n, "x")),
+    }
+}
+
+/// Parse the presets from a GetPresets response
+pub fn parse_presets(xml: &str) -> Result<Vec<Preset>> {
+    let doc = roxmltree::Document::parse(xml)?;
+
+    Ok(doc
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "Preset")
+        .map(|n| Preset {
+            token: n.attribute("token").unwrap_or_default().to_string(),
+            name: xml::child_parse(n, "Name").unwrap_or_default(),
+            position: xml::child(n, "PTZPosition").map(parse_vector),
+        })
+        .collect())
+}
+
+/// Parse the status from a GetStatus response
+pub fn parse_status(xml: &str) -> Result<PtzStatus> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let node = xml::descendant(doc.root(), "PTZStatus")
+        .ok_or_else(|| Error::InvalidResponse("missing PTZStatus".into()))?;
+    let position = xml::child(node, "Position");
+    let space = |name| {
+        position
+            .and_then(|n| xml::child(n, name))
+            .and_then(|n| n.attribute("space"))
+            .map(String::from)
+    };
+    let move_status = |name| {
+        xml::child(node, "MoveStatus")
+            .and_then(|n| xml::child_text(n, name))
+            .map(MoveStatus::parse)
+    };
+
+    Ok(PtzStatus {
+        position: position.map(parse_vector).unwrap_or_default(),
+        pan_tilt_space: space("PanTilt"),
+        zoom_space: space("Zoom"),
+        pan_tilt_status: move_status("PanTilt"),
+        zoom_status: move_status("Zoom"),
+        error: xml::child_text(node, "Error")
+            .filter(|e| !e.is_empty())
+            .map(String::from),
+        utc_time: xml::child_text(node, "UtcTime")
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc)),
+    })
+}
+
+/// Parse the token of the preset from a SetPreset response
+pub fn parse_set_preset(xml: &str) -> Result<String> {
+    let doc = roxmltree::Document::parse(xml)?;
+    xml::descendant(doc.root(), "SetPresetResponse")
+        .ok_or_else(|| Error::InvalidResponse("missing SetPresetResponse".into()))
+        .and_then(|n| xml::required(n, "PresetToken"))
+}
+
+/// Parse the response data from a SendAuxiliaryCommand response, empty if the
+/// camera did not return any
+pub fn parse_auxiliary_response(xml: &str) -> Result<String> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let node = xml::descendant(doc.root(), "SendAuxiliaryCommandResponse")
+        .ok_or_else(|| Error::InvalidResponse("missing SendAuxiliaryCommandResponse".into()))?;
+    Ok(xml::child_parse(node, "AuxiliaryResponse").unwrap_or_default())
+}
+
+/// Parse a `tt:FloatRange` element
+pub(crate) fn parse_float_range(node: Node) -> Result<FloatRange> {
+    Ok(FloatRange {
+        min: xml::required(node, "Min")?,
+        max: xml::required(node, "Max")?,
+    })
+}
+
+/// Parse a space element, made of an `URI` and X and optional Y ranges
+pub(crate) fn parse_space(node: Node) -> Result<Space> {
+    let range = |name| xml::child(node, name).map(parse_float_range).transpose();
+
+    Ok(Space {
+        uri: xml::required(node, "URI")?,
+        x_range: xml::required_child(node, "XRange").and_then(parse_float_range)?,
+        y_range: range("YRange")?,
+    })
+}
+
+/// Parse a `tt:PTZSpaces` element
+fn parse_spaces(node: Node) -> Result<PtzSpaces> {
+    let spaces = |name| {
+        xml::children(node, name)
+            .map(parse_space)
+            .collect::<Result<Vec<_>>>()
+    };
+
+    Ok(PtzSpaces {
+        absolute_pan_tilt_position: spaces("AbsolutePanTiltPositionSpace")?,
+        absolute_zoom_position: spaces("AbsoluteZoomPositionSpace")?,
+        relative_pan_tilt_translation: spaces("RelativePanTiltTranslationSpace")?,
+        relative_zoom_translation: spaces("RelativeZoomTranslationSpace")?,
+        continuous_pan_tilt_velocity: spaces("ContinuousPanTiltVelocitySpace")?,
+        continuous_zoom_velocity: spaces("ContinuousZoomVelocitySpace")?,
+        pan_tilt_speed: spaces("PanTiltSpeedSpace")?,
+        zoom_speed: spaces("ZoomSpeedSpace")?,
+    })
+}
+
+/// Parse a `tt:PTZNode` element
+fn parse_node(node: Node) -> Result<PtzNode> {
+    Ok(PtzNode {
+        token: node.attribute("token").unwrap_or_default().to_string(),
+        name: xml::child_parse(node, "Name").unwrap_or_default(),
+        fixed_home_position: node
+            .attribute("FixedHomePosition")
+            .and_then(xml::parse_bool),
+        supported_spaces: xml::required_child(node, "SupportedPTZSpaces").and_then(parse_spaces)?,
+        maximum_number_of_presets: xml::child_parse(node, "MaximumNumberOfPresets")
+            .unwrap_or_default(),
+        home_supported: xml::child_text(node, "HomeSupported")
+            .and_then(xml::parse_bool)
+            .unwrap_or(false),
+        auxiliary_commands: xml::children(node, "AuxiliaryCommands")
+            .filter_map(|n| n.text())
+            .map(|t| t.trim().to_string())
+            .collect(),
+    })
+}
+
+/// Parse the nodes from a GetNodes or GetNode response
+pub fn parse_nodes(xml: &str) -> Result<Vec<PtzNode>> {
+    roxmltree::Document::parse(xml)?
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "PTZNode")
+        .map(parse_node)
+        .collect()
+}
+
+/// Parse the configurations from a GetConfigurations response
+pub fn parse_configurations(xml: &str) -> Result<Vec<PtzConfiguration>> {
+    roxmltree::Document::parse(xml)?
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "PTZConfiguration")
+        .map(parse_configuration)
+        .collect()
+}
+
+/// Parse the options from a GetConfigurationOptions response
+pub fn parse_configuration_options(xml: &str) -> Result<PtzConfigurationOptions> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let node = xml::descendant(doc.root(), "PTZConfigurationOptions")
+        .ok_or_else(|| Error::InvalidResponse("missing PTZConfigurationOptions".into()))?;
+    let timeout_range = xml::child(node, "PTZTimeout").and_then(|n| {
+        let duration = |name| xml::child_text(n, name).and_then(xml::parse_duration);
+        Some((duration("Min")?, duration("Max")?))
+    });
+
+    Ok(PtzConfigurationOptions {
+        spaces: xml::required_child(node, "Spaces").and_then(parse_spaces)?,
+        timeout_range,
+    })
+}
+
+/// Parse a `tt:PTZConfiguration` element
+pub(crate) fn parse_configuration(node: Node) -> Result<PtzConfiguration> {
+    let limits = |name| {
+        xml::child(node, name)
+            .and_then(|n| xml::child(n, "Range"))
+            .and_then(|n| {
+                parse_space(n)
+                    .map_err(|e| warn!("ignoring invalid {}: {}", name, e))
+                    .ok()
+            })
+    };
+
+    Ok(PtzConfiguration {
+        token: node.attribute("token").unwrap_or_default().to_string(),
+        name: xml::child_parse(node, "Name").unwrap_or_default(),
+        node_token: xml::required(node, "NodeToken")?,
+        default_timeout: xml::child_text(node, "DefaultPTZTimeout").and_then(xml::parse_duration),
+        pan_tilt_limits: limits("PanTiltLimits"),
+        zoom_limits: limits("ZoomLimits"),
+    })
+}
@@ -0,0 +1,77 @@
+//! ONVIF services and endpoint discovery
+
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+use crate::namespaces::*;
+use crate::xml;
+
+/// ONVIF service targeted by a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Service {
+    Device,
+    Media,
+    Ptz,
+}
+
+impl Service {
+    /// WSDL namespace of the service
+    pub fn namespace(self) -> &'static str {
+        match self {
+            Service::Device => OVF_DEVICE,
+            Service::Media => OVF_MEDIA,
+            Service::Ptz => OVF_PTZ,
+        }
+    }
+
+    fn from_namespace(namespace: &str) -> Option<Self> {
+        [Service::Device, Service::Media, Service::Ptz]
+            .iter()
+            .copied()
+            .find(|s| s.namespace() == namespace)
+    }
+}
+
+/// Parse the service addresses from a GetServices response
+pub fn parse_services(xml: &str) -> Result<HashMap<Service, String>> {
+    let doc = roxmltree::Document::parse(xml)?;
+
+    let services: HashMap<_, _> = doc
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "Service")
+        .filter_map(|n| {
+            let service = Service::from_namespace(xml::child_text(n, "Namespace")?)?;
+            let xaddr = xml::child_text(n, "XAddr")?;
+            Some((service, xaddr.to_string()))
+        })
+        .collect();
+
+    if services.is_empty() {
+        return Err(Error::InvalidResponse("no known service".into()));
+    }
+    Ok(services)
+}
+
+/// Parse the service addresses from a GetCapabilities response
+pub fn parse_capabilities(xml: &str) -> Result<HashMap<Service, String>> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let capabilities = xml::descendant(doc.root(), "Capabilities")
+        .ok_or_else(|| Error::InvalidResponse("missing Capabilities".into()))?;
+
+    let services: HashMap<_, _> = [
+        (Service::Device, "Device"),
+        (Service::Media, "Media"),
+        (Service::Ptz, "PTZ"),
+    ]
+    .iter()
+    .filter_map(|&(service, name)| {
+        let xaddr = xml::child_text(xml::child(capabilities, name)?, "XAddr")?;
+        Some((service, xaddr.to_string()))
+    })
+    .collect();
+
+    if services.is_empty() {
+        return Err(Error::InvalidResponse("no known service".into()));
+    }
+    Ok(services)
+}
@@ -0,0 +1,194 @@
+//! State shared by the blocking and async cameras
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::auth;
+use crate::device;
+use crate::error::{Error, Result};
+use crate::media::MediaUri;
+use crate::namespaces::*;
+use crate::ptz::{self, PtzSpaces, PtzVector, RangePolicy, TranslationSpace};
+use crate::services::Service;
+
+/// Camera address, credentials and discovered state. The I/O is done by the
+/// camera types, this only holds what does not depend on the HTTP client.
+#[derive(Debug)]
+pub struct Session {
+    address: String,
+    pub profile: Option<String>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub services: HashMap<Service, String>,
+    /// Spaces of the PTZ node, used to check the move values
+    pub ptz_spaces: Option<PtzSpaces>,
+    pub range_policy: RangePolicy,
+    clock_skew: Mutex<Option<chrono::Duration>>,
+}
+
+impl Session {
+    /// Parse the address and extract the credentials, the url path
+    /// "/onvif/device_service" is automatically set
+    pub fn new(address: &str, profile: Option<&str>) -> Result<Self> {
+        let mut u = url::Url::parse(address)?;
+        u.set_path("/onvif/device_service");
+
+        let user = match u.username() {
+            "" => None,
+            name => Some(name.to_string()),
+        };
+        let password = u.password().map(String::from);
+
+        Ok(Self {
+            address: u.to_string(),
+            profile: profile.map(String::from),
+            user,
+            password,
+            services: HashMap::new(),
+            ptz_spaces: None,
+            range_policy: RangePolicy::default(),
+            clock_skew: Mutex::new(None),
+        })
+    }
+
+    /// Get the profile token
+    // Return a Result so that we can use "?" in other methods
+    pub fn profile_token(&self) -> Result<&str> {
+        self.profile.as_deref().ok_or(Error::MissingProfile)
+    }
+
+    /// Get the address where the requests for `service` are sent
+    pub fn service_address(&self, service: Service) -> &str {
+        self.services
+            .get(&service)
+            .map(String::as_str)
+            .unwrap_or(&self.address)
+    }
+
+    /// True if requests are authenticated
+    pub fn has_credentials(&self) -> bool {
+        self.user.is_some() && self.password.is_some()
+    }
+
+    /// Return a new SOAP header element for authentication, with the creation
+    /// date shifted by `clock_skew`
+    pub fn auth_header(&self, clock_skew: chrono::Duration) -> Option<String> {
+        match (&self.user, &self.password) {
+            (Some(ref u), Some(ref p)) => Some(auth::get_auth_header(u, p, clock_skew)),
+            _ => None,
+        }
+    }
+
+    /// Get the measured clock skew, or None if not measured yet
+    pub fn clock_skew(&self) -> Option<chrono::Duration> {
+        *self.clock_skew.lock().unwrap()
+    }
+
+    /// Store the clock skew used for the next authentication headers
+    pub fn set_clock_skew(&self, skew: chrono::Duration) {
+        *self.clock_skew.lock().unwrap() = Some(skew);
+    }
+
+    /// Forget the clock skew, so that it is measured again on next use
+    pub fn reset_clock_skew(&self) {
+        *self.clock_skew.lock().unwrap() = None;
+    }
+
+    /// Compute and store the clock skew from a GetSystemDateAndTime response
+    /// received between `before` and `after`
+    pub fn update_clock_skew(
+        &self,
+        resp: &str,
+        before: chrono::DateTime<chrono::Utc>,
+        after: chrono::DateTime<chrono::Utc>,
+    ) -> Result<chrono::Duration> {
+        // Assume the camera time is taken halfway through the request
+        let local = before + (after - before) / 2;
+        let skew = device::parse_utc_datetime(resp)? - local;
+        debug!("camera clock skew: {}", skew);
+
+        self.set_clock_skew(skew);
+        Ok(skew)
+    }
+
+    /// Insert the credentials in the media URI if they are set
+    pub fn add_uri_credentials(&self, uri: &mut MediaUri) -> Result<()> {
+        if let Some(ref user) = self.user {
+            uri.set_credentials(user, self.password.as_deref())?;
+        }
+        Ok(())
+    }
+
+    /// Return the HTTP `Authorization` header answering the challenges of a
+    /// 401 response to a GET request on `uri`
+    pub fn http_authorization(&self, uri: &str, challenges: &[&str]) -> Result<Option<String>> {
+        let user = match self.user {
+            Some(ref user) => user,
+            None => return Ok(None),
+        };
+
+        let u = url::Url::parse(uri)?;
+        let path = &u[url::Position::BeforePath..url::Position::AfterQuery];
+        Ok(auth::http_auth_header(
+            challenges,
+            "GET",
+            path,
+            user,
+            self.password.as_deref().unwrap_or_default(),
+        ))
+    }
+
+    /// Check a vector sent in the given spaces, see `ptz::check_vector()`
+    fn check_vector(
+        &self,
+        vector: &PtzVector,
+        pan_tilt_space: &str,
+        zoom_space: &str,
+    ) -> Result<PtzVector> {
+        ptz::check_vector(
+            vector,
+            pan_tilt_space,
+            zoom_space,
+            self.ptz_spaces.as_ref(),
+            self.range_policy,
+        )
+    }
+
+    /// Check the velocity of a continuous move
+    pub fn check_velocity(&self, velocity: &PtzVector) -> Result<PtzVector> {
+        check_not_empty(velocity, "velocity")?;
+        self.check_vector(velocity, OVF_PTS_VGS, OVF_ZS_VGS)
+    }
+
+    /// Check the translation of a relative move
+    pub fn check_translation(
+        &self,
+        translation: &PtzVector,
+        space: TranslationSpace,
+    ) -> Result<PtzVector> {
+        check_not_empty(translation, "translation")?;
+        self.check_vector(translation, space.uri(), OVF_ZS_TGS)
+    }
+
+    /// Check the position of an absolute move
+    pub fn check_position(&self, position: &PtzVector) -> Result<PtzVector> {
+        check_not_empty(position, "position")?;
+        self.check_vector(position, OVF_PTS_PGS, OVF_ZS_PGS)
+    }
+
+    /// Check the optional speed of a move
+    pub fn check_speed(&self, speed: Option<&PtzVector>) -> Result<Option<PtzVector>> {
+        speed
+            .map(|s| self.check_vector(s, OVF_PTS_GSS, OVF_ZS_ZGSS))
+            .transpose()
+    }
+}
+
+/// Return an error for a vector without any component, which would be sent as
+/// an empty and invalid element
+fn check_not_empty(vector: &PtzVector, name: &str) -> Result<()> {
+    if vector.is_empty() {
+        return Err(Error::InvalidArgument(format!("empty PTZ {}", name)));
+    }
+    Ok(())
+}
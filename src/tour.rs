@@ -0,0 +1,247 @@
+//! PTZ preset tour types and responses
+
+use std::fmt;
+use std::time::Duration;
+
+use roxmltree::Node;
+
+use crate::error::{Error, Result};
+use crate::ptz::{self, PtzVector};
+use crate::xml;
+
+/// State of a preset tour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourState {
+    Idle,
+    Touring,
+    Paused,
+    /// Any other state, such as a vendor extension. It cannot be sent in
+    /// ModifyPresetTour.
+    Unknown,
+}
+
+impl TourState {
+    fn parse(s: &str) -> Self {
+        match s {
+            "Idle" => TourState::Idle,
+            "Touring" => TourState::Touring,
+            "Paused" => TourState::Paused,
+            _ => TourState::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for TourState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TourState::Idle => "Idle",
+            TourState::Touring => "Touring",
+            TourState::Paused => "Paused",
+            TourState::Unknown => "Unknown",
+        })
+    }
+}
+
+/// Order in which the spots of a tour are visited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourDirection {
+    Forward,
+    Backward,
+}
+
+impl TourDirection {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "Forward" => Some(TourDirection::Forward),
+            "Backward" => Some(TourDirection::Backward),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TourDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TourDirection::Forward => "Forward",
+            TourDirection::Backward => "Backward",
+        })
+    }
+}
+
+/// Operation requested with OperatePresetTour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourOperation {
+    Start,
+    Stop,
+    Pause,
+}
+
+impl fmt::Display for TourOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TourOperation::Start => "Start",
+            TourOperation::Stop => "Stop",
+            TourOperation::Pause => "Pause",
+        })
+    }
+}
+
+/// Stop of a preset tour
+#[derive(Debug, Clone, PartialEq)]
+pub struct TourSpot {
+    pub preset_token: String,
+    /// Speed of the move to the preset, the camera default if None
+    pub speed: Option<PtzVector>,
+    /// Time spent at the preset before moving to the next one
+    pub stay_time: Option<Duration>,
+}
+
+/// Conditions of the start and of the repetition of a tour
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TourStartingCondition {
+    /// Number of repetitions, None for the device default
+    pub recurring_time: Option<u32>,
+    /// Duration of the repetitions, None for the device default
+    pub recurring_duration: Option<Duration>,
+    pub direction: Option<TourDirection>,
+    /// Visit the spots in a random order
+    pub random_preset_order: bool,
+}
+
+/// Preset tour, a patrol over presets executed by the camera
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresetTour {
+    pub token: String,
+    pub name: String,
+    pub state: TourState,
+    /// True if the tour starts when the device boots
+    pub auto_start: bool,
+    pub starting_condition: TourStartingCondition,
+    /// Stops of the tour. Spots referring to a position or to the home
+    /// position instead of a preset are not supported and are skipped.
+    pub spots: Vec<TourSpot>,
+}
+
+/// Valid values for a preset tour, returned by GetPresetTourOptions
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresetTourOptions {
+    /// True if tours can be started automatically
+    pub auto_start: bool,
+    /// Minimum and maximum number of repetitions
+    pub recurring_time_range: Option<(u32, u32)>,
+    /// Minimum and maximum duration of the repetitions
+    pub recurring_duration_range: Option<(Duration, Duration)>,
+    pub directions: Vec<TourDirection>,
+    /// Tokens of the presets usable as tour spots
+    pub preset_tokens: Vec<String>,
+    /// Minimum and maximum stay time at each spot
+    pub stay_time_range: Option<(Duration, Duration)>,
+}
+
+/// Parse a `Min`/`Max` range element of durations
+fn duration_range(node: Node) -> Option<(Duration, Duration)> {
+    let duration = |name| xml::child_text(node, name).and_then(xml::parse_duration);
+    Some((duration("Min")?, duration("Max")?))
+}
+
+fn parse_spot(node: Node) -> Option<TourSpot> {
+    let preset_token =
+        xml::child(node, "PresetDetail").and_then(|n| xml::child_parse(n, "PresetToken"));
+    if preset_token.is_none() {
+        debug!("skipping tour spot without preset token");
+    }
+
+    Some(TourSpot {
+        preset_token: preset_token?,
+        speed: xml::child(node, "Speed").map(ptz::parse_vector),
+        stay_time: xml::child_text(node, "StayTime").and_then(xml::parse_duration),
+    })
+}
+
+fn parse_tour(node: Node) -> PresetTour {
+    let condition = xml::child(node, "StartingCondition");
+
+    PresetTour {
+        token: node.attribute("token").unwrap_or_default().to_string(),
+        name: xml::child_parse(node, "Name").unwrap_or_default(),
+        state: xml::child(node, "Status")
+            .and_then(|n| xml::child_text(n, "State"))
+            .map_or(TourState::Unknown, TourState::parse),
+        auto_start: xml::child_text(node, "AutoStart")
+            .and_then(xml::parse_bool)
+            .unwrap_or(false),
+        starting_condition: condition
+            .map(|n| TourStartingCondition {
+                recurring_time: xml::child_parse(n, "RecurringTime"),
+                recurring_duration: xml::child_text(n, "RecurringDuration")
+                    .and_then(xml::parse_duration),
+                direction: xml::child_text(n, "Direction").and_then(TourDirection::parse),
+                random_preset_order: n
+                    .attribute("RandomPresetOrder")
+                    .and_then(xml::parse_bool)
+                    .unwrap_or(false),
+            })
+            .unwrap_or_default(),
+        spots: xml::children(node, "TourSpot")
+            .filter_map(parse_spot)
+            .collect(),
+    }
+}
+
+/// Parse the tours from a GetPresetTours response
+pub fn parse_preset_tours(xml: &str) -> Result<Vec<PresetTour>> {
+    Ok(roxmltree::Document::parse(xml)?
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "PresetTour")
+        .map(parse_tour)
+        .collect())
+}
+
+/// Parse the options from a GetPresetTourOptions response
+pub fn parse_preset_tour_options(xml: &str) -> Result<PresetTourOptions> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let node = xml::descendant(doc.root(), "Options")
+        .ok_or_else(|| Error::InvalidResponse("missing Options".into()))?;
+    let condition = xml::child(node, "StartingCondition");
+    let spot = xml::child(node, "TourSpot");
+
+    Ok(PresetTourOptions {
+        auto_start: xml::child_text(node, "AutoStart")
+            .and_then(xml::parse_bool)
+            .unwrap_or(false),
+        recurring_time_range: condition
+            .and_then(|n| xml::child(n, "RecurringTime"))
+            .and_then(|n| Some((xml::child_parse(n, "Min")?, xml::child_parse(n, "Max")?))),
+        recurring_duration_range: condition
+            .and_then(|n| xml::child(n, "RecurringDuration"))
+            .and_then(duration_range),
+        directions: condition
+            .map(|n| {
+                xml::children(n, "Direction")
+                    .filter_map(|c| c.text())
+                    .filter_map(|t| TourDirection::parse(t.trim()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        preset_tokens: spot
+            .and_then(|n| xml::child(n, "PresetDetail"))
+            .map(|n| {
+                xml::children(n, "PresetToken")
+                    .filter_map(|c| c.text())
+                    .map(|t| t.trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        stay_time_range: spot
+            .and_then(|n| xml::child(n, "StayTime"))
+            .and_then(duration_range),
+    })
+}
+
+/// Parse the token of the tour from a CreatePresetTour response
+pub fn parse_create_preset_tour(xml: &str) -> Result<String> {
+    let doc = roxmltree::Document::parse(xml)?;
+    xml::descendant(doc.root(), "CreatePresetTourResponse")
+        .ok_or_else(|| Error::InvalidResponse("missing CreatePresetTourResponse".into()))
+        .and_then(|n| xml::required(n, "PresetTourToken"))
+}
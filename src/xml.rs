@@ -0,0 +1,122 @@
+//! Helpers for walking ONVIF responses
+//!
+//! Cameras use various prefixes for the same namespaces, so the elements are
+//! matched by local name only.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use roxmltree::Node;
+
+use crate::error::{Error, Result};
+
+/// Return the first child element with the given local name
+pub fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|n| n.is_element() && n.tag_name().name() == name)
+}
+
+/// Return the child elements with the given local name
+pub fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |n| n.is_element() && n.tag_name().name() == name)
+}
+
+/// Return the first descendant element with the given local name
+pub fn descendant<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.descendants()
+        .find(|n| n.is_element() && n.tag_name().name() == name)
+}
+
+/// Return the trimmed text of the first child element with the given local name
+pub fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name).and_then(|n| n.text()).map(str::trim)
+}
+
+/// Parse the text of the first child element with the given local name
+pub fn child_parse<T: FromStr>(node: Node, name: &str) -> Option<T> {
+    child_text(node, name).and_then(|t| t.parse().ok())
+}
+
+/// Parse the text of the first child element with the given local name,
+/// returning an error if it is missing or invalid
+pub fn required<T: FromStr>(node: Node, name: &str) -> Result<T> {
+    child_parse(node, name).ok_or_else(|| {
+        Error::InvalidResponse(format!(
+            "missing or invalid {} in {}",
+            name,
+            node.tag_name().name()
+        ))
+    })
+}
+
+/// Return the first child element with the given local name, or an error if
+/// it is missing
+pub fn required_child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Result<Node<'a, 'input>> {
+    child(node, name).ok_or_else(|| {
+        Error::InvalidResponse(format!("missing {} in {}", name, node.tag_name().name()))
+    })
+}
+
+/// Parse an `xs:boolean` value
+pub fn parse_bool(s: &str) -> Option<bool> {
+    match s.trim() {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse an `xs:duration` value such as `PT1M30S`. Years and months are not
+/// supported since their duration is not fixed, and negative or non-finite
+/// values are rejected.
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim().strip_prefix('P')?;
+    let (date, time) = match s.find('T') {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => (s, ""),
+    };
+
+    let mut secs = 0.0f64;
+    for (part, units) in [
+        (date, &[('D', 86400.0)][..]),
+        (time, &[('H', 3600.0), ('M', 60.0), ('S', 1.0)][..]),
+    ]
+    .iter()
+    {
+        let mut rest = *part;
+        for &(unit, factor) in units.iter() {
+            if let Some(i) = rest.find(unit) {
+                secs += rest[..i].parse::<f64>().ok()? * factor;
+                rest = &rest[i + 1..];
+            }
+        }
+        if !rest.is_empty() {
+            return None;
+        }
+    }
+
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT60S"), Some(Duration::from_secs(60)));
+        assert_eq!(parse_duration("PT0H3M0S"), Some(Duration::from_secs(180)));
+        assert_eq!(parse_duration("PT0.5S"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_duration("P1DT1S"), Some(Duration::from_secs(86401)));
+        assert_eq!(parse_duration("P1M"), None);
+        assert_eq!(parse_duration("60"), None);
+        assert_eq!(parse_duration("PT-5S"), None);
+        assert_eq!(parse_duration("PTNaNS"), None);
+        assert_eq!(parse_duration("PTinfS"), None);
+        assert_eq!(parse_duration("PT1e400S"), None);
+    }
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "async")]
+
+// Test the async GetProfiles request, using captures from TP-LINK TL-IPC43AN-4
+#[tokio::test]
+async fn test_async_get_profiles_tplink() {
+    let resp_xml = include_str!("captures/get_profiles_response_tplink.xml");
+    let url = mockito::server_url();
+    let _mock = mockito::mock("POST", "/onvif/device_service")
+        .with_header("Content-Type", "application/soap+xml; charset=utf-8")
+        .with_body(resp_xml)
+        .create();
+
+    let camera = simpleonvif::AsyncOnvifCamera::new(&url, None).unwrap();
+    let profiles = camera.get_profile_tokens().await.unwrap();
+    assert_eq!(&profiles[..], &["profile_1", "profile_2"])
+}
+
+// Test the decoding of a SOAP fault by the async camera
+#[tokio::test]
+async fn test_async_fault_not_authorized() {
+    let resp_xml = include_str!("captures/fault_not_authorized.xml");
+    let url = mockito::server_url();
+    let _mock = mockito::mock("POST", "/onvif/device_service")
+        .with_status(400)
+        .with_header("Content-Type", "application/soap+xml; charset=utf-8")
+        .with_body(resp_xml)
+        .create();
+
+    let camera = simpleonvif::AsyncOnvifCamera::new(&url, None).unwrap();
+    let err = camera.get_profiles().await.unwrap_err();
+    assert!(err.is_fault(&simpleonvif::FaultSubcode::NotAuthorized));
+}
+
+// Test the async service discovery, the following requests must be sent to
+// the discovered endpoints
+#[tokio::test]
+async fn test_async_connect_get_services() {
+    let url = mockito::server_url();
+    let resp_xml = include_str!("captures/get_services_response.xml").replace("{server}", &url);
+    let _mock_services = mockito::mock("POST", "/onvif/device_service")
+        .match_body(mockito::Matcher::Regex("<GetServices ".into()))
+        .with_body(resp_xml)
+        .create();
+    let _mock_profiles = mockito::mock("POST", "/onvif/media_service")
+        .match_body(mockito::Matcher::Regex("<GetProfiles ".into()))
+        .with_body(include_str!("captures/get_profiles_response_hzsoar.xml"))
+        .create();
+
+    let mut camera = simpleonvif::AsyncOnvifCamera::new(&url, None).unwrap();
+    camera.connect().await.unwrap();
+    let profiles = camera.get_profile_tokens().await.unwrap();
+    assert_eq!(&profiles[..], &["MediaProfile000", "MediaProfile001"])
+}
+
+// Test the async polling of the PTZ status after a preset move
+#[tokio::test]
+async fn test_async_goto_preset_and_wait() {
+    let url = mockito::server_url();
+    let _mock_presets = mockito::mock("POST", "/onvif/device_service")
+        .match_body(mockito::Matcher::Regex("<GetPresets ".into()))
+        .with_body(include_str!("captures/get_presets_response.xml"))
+        .create();
+    let mock_goto = mockito::mock("POST", "/onvif/device_service")
+        .match_body(mockito::Matcher::Regex(
+            "<PresetToken>1</PresetToken>".into(),
+        ))
+        .expect(1)
+        .create();
+    let _mock_status = mockito::mock("POST", "/onvif/device_service")
+        .match_body(mockito::Matcher::Regex("<GetStatus ".into()))
+        .with_body(include_str!("captures/get_status_response.xml"))
+        .create();
+
+    let camera = simpleonvif::AsyncOnvifCamera::new(&url, Some("profile_1")).unwrap();
+    let wait = simpleonvif::WaitSetup {
+        poll_interval: std::time::Duration::from_millis(10),
+        timeout: std::time::Duration::from_millis(50),
+        tolerance: 0.01,
+    };
+    // The status position is not the one of the preset and never becomes idle
+    let err = camera
+        .goto_preset_and_wait("1", None, &wait)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, simpleonvif::Error::MoveTimeout(_)));
+    mock_goto.assert();
+}